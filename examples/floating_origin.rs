@@ -1,7 +1,8 @@
 //! Demonstrates the use of this camera controller in a very large world.
 //!
 //! This works by integrating with the `big_space` crate, which provides a grid-based
-//! "floating origin" transform system for supporting worlds larger than 64bit.
+//! "floating origin" transform system for supporting worlds larger than 64bit. Run it with the
+//! `big_space` feature, which provides a [`TransformAdapter`] for cameras in a grid.
 //!
 //! This demonstrates that the camera controller can handle being teleported between grid
 //! cells, resetting the `Transform`, without disrupting smoothed user inputs or momentum.
@@ -22,6 +23,9 @@ fn main() {
             bevy_framepace::FramepacePlugin,
         ))
         .add_plugins(DefaultEditorCamPlugins)
+        // Read the camera position from its grid cell, so it is stable when the camera is
+        // recentered in a new cell. Requires the `big_space` feature.
+        .insert_resource(TransformAdapter::big_space())
         .insert_resource(ClearColor(Color::BLACK))
         .insert_resource(GlobalAmbientLight {
             color: Color::WHITE,
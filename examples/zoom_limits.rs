@@ -33,6 +33,7 @@ fn setup_camera(mut commands: Commands, asset_server: Res<AssetServer>) {
                 min_size_per_pixel: 0.0001,
                 max_size_per_pixel: 0.01,
                 zoom_through_objects: true,
+                ..default()
             },
             ..default()
         },
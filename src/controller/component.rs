@@ -14,18 +14,25 @@ use bevy_reflect::prelude::*;
 use bevy_time::prelude::*;
 use bevy_transform::prelude::*;
 
-use super::transform_adapter::TransformAdapter;
+use super::{
+    projection_adapter::ProjectionAdapters,
+    transform_adapter::{ParentGlobalTransform, TransformAdapter},
+};
 use bevy_window::RequestRedraw;
 
 use super::{
     inputs::MotionInputs,
     momentum::{Momentum, Velocity},
     motion::CurrentMotion,
-    projections::{OrthographicSettings, PerspectiveSettings},
+    projections::{OrthographicSettings, PerspectiveSettings, PerspectiveZoomMode},
     smoothing::{InputQueue, Smoothing},
-    zoom::ZoomLimits,
+    zoom::{ZoomLimit, ZoomLimitReached, ZoomLimits},
 };
 
+/// The fraction an orthographic projection's scale changes for one unit of zoom input. Hand-tuned
+/// to feel equivalent to perspective zoom.
+const ORTHO_ZOOM_PER_INPUT: f64 = 0.0015;
+
 /// Tracks all state of a camera's controller, including its inputs, motion, and settings.
 ///
 /// See the documentation on the contained fields and types to learn more about each setting.
@@ -48,6 +55,8 @@ use super::{
 ///    [`EditorCam::send_zoom_input`].
 /// 3. When the motion should end, call  [`EditorCam::end_move`].
 #[derive(Debug, Clone, Reflect, Component)]
+#[require(ParentGlobalTransform)]
+#[cfg_attr(feature = "big_space", require(super::transform_adapter::ParentGrid))]
 pub struct EditorCam {
     /// What input motions are currently allowed?
     pub enabled_motion: EnabledMotion,
@@ -80,6 +89,9 @@ pub struct EditorCam {
     /// Current camera motion. Managed by the camera controller, but exposed publicly to allow for
     /// overriding motion.
     pub current_motion: CurrentMotion,
+    /// The zoom limit the camera was held against in its last update, if any. Managed by the camera
+    /// controller, and used to send [`ZoomLimitReached`] only when a limit is first reached.
+    pub reached_zoom_limit: Option<ZoomLimit>,
 }
 
 impl Default for EditorCam {
@@ -96,6 +108,7 @@ impl Default for EditorCam {
             enabled_motion: Default::default(),
             current_motion: Default::default(),
             last_anchor_depth: -2.0,
+            reached_zoom_limit: None,
         }
     }
 }
@@ -173,18 +186,33 @@ impl EditorCam {
     /// Get the position of the anchor in world space.
     pub fn anchor_world_space(&self, camera_transform: &GlobalTransform) -> Option<DVec3> {
         self.anchor_view_space().map(|anchor_view_space| {
-            camera_transform
-                .to_matrix()
-                .as_dmat4()
-                .transform_point3(anchor_view_space)
-        });
-
-        self.anchor_view_space().map(|anchor_view_space| {
-            let (_, r, t) = camera_transform.to_scale_rotation_translation();
-            r.as_dquat() * anchor_view_space + t.as_dvec3()
+            Self::view_to_world(camera_transform).transform_point3(anchor_view_space)
         })
     }
 
+    /// The transform from the view space of a camera to world space.
+    ///
+    /// Unlike the [`GlobalTransform`] of the camera, this does not include the scale of the camera
+    /// or its parents, so distances in view space are the same as distances in world space. All
+    /// view space positions used by the controller, like the anchor, are in this space.
+    pub fn view_to_world(camera_transform: &GlobalTransform) -> DMat4 {
+        let (_, rotation, translation) = camera_transform.to_scale_rotation_translation();
+        DMat4::from_rotation_translation(rotation.as_dquat(), translation.as_dvec3())
+    }
+
+    /// The length of a world unit along the view axis of the camera, in the view space of its
+    /// [`GlobalTransform`]. The clipping planes of a [`Projection`] are in that space, which
+    /// includes the scale of the camera and its parents, so divide a depth from
+    /// [`Self::view_to_world`] by this to get the distance of a clipping plane.
+    pub fn depth_scale(camera_transform: &GlobalTransform) -> f64 {
+        let scale = camera_transform.affine().matrix3.z_axis.length() as f64;
+        if scale.is_normal() {
+            scale
+        } else {
+            1.0
+        }
+    }
+
     /// Should the camera controller prevent new motions from starting because the user is actively
     /// operating the camera?
     ///
@@ -286,6 +314,17 @@ impl EditorCam {
         }
     }
 
+    /// Send zoom inputs that are applied immediately, without input smoothing, and that do not
+    /// cause zoom momentum. Use this for inputs that are already eased, like the steps of a
+    /// [`ScrollZoom`](crate::input::scroll::ScrollZoom).
+    pub fn send_unsmoothed_zoom_input(&mut self, zoom_amount: f32) {
+        if let CurrentMotion::UserControlled { motion_inputs, .. } = &mut self.current_motion {
+            motion_inputs
+                .zoom_inputs_mut()
+                .process_unsmoothed_input(zoom_amount)
+        }
+    }
+
     /// End the current camera motion, allowing other motions on this camera to begin. See
     /// [`EditorCam`] for usage.
     pub fn end_move(&mut self) {
@@ -305,7 +344,10 @@ impl EditorCam {
                     anchor,
                     velocity: motion_inputs.pan_momentum(self.momentum.init_pan),
                 },
-                MotionInputs::Zoom { .. } => Velocity::None,
+                MotionInputs::Zoom { .. } => Velocity::Zoom {
+                    anchor,
+                    velocity: motion_inputs.zoom_momentum(self.momentum.init_zoom),
+                },
             },
         };
         let momentum_start = Instant::now();
@@ -323,7 +365,9 @@ impl EditorCam {
             Query<EntityMut, With<EditorCam>>,
         )>,
         transform_adapter: Res<TransformAdapter>,
+        projection_adapters: Res<ProjectionAdapters>,
         mut event: MessageWriter<RequestRedraw>,
+        mut limit_events: MessageWriter<ZoomLimitReached>,
         time: Res<Time>,
     ) {
         camera_set
@@ -343,14 +387,30 @@ impl EditorCam {
                     .ok()
                     .and_then(|(mut camera_controller, camera, projection)| {
                         let dt = time.delta();
-                        camera_controller.update_transform_and_projection(
+                        let mut limit_reached = None;
+                        let delta = camera_controller.update_with_limits(
                             camera,
                             original_translation,
                             original_rotation,
                             projection,
+                            &projection_adapters,
                             &mut event,
                             dt,
-                        )
+                            &mut limit_reached,
+                        );
+                        // Only report a limit when the camera first reaches it, not on every
+                        // frame the camera is held against it.
+                        let previous = std::mem::replace(
+                            &mut camera_controller.reached_zoom_limit,
+                            limit_reached,
+                        );
+                        if let Some(limit) = limit_reached.filter(|l| previous != Some(*l)) {
+                            limit_events.write(ZoomLimitReached {
+                                camera: *entity,
+                                limit,
+                            });
+                        }
+                        delta
                     })
                     .map(|transform| (*entity, transform))
             })
@@ -368,30 +428,78 @@ impl EditorCam {
     }
 
     /// Update this [`EditorCam`]'s transform and projection.
+    #[allow(clippy::too_many_arguments)]
     pub fn update_transform_and_projection(
+        &mut self,
+        camera: &Camera,
+        original_translation: &DVec3,
+        original_rotation: &DQuat,
+        projection: Mut<Projection>,
+        projection_adapters: &ProjectionAdapters,
+        redraw: &mut MessageWriter<RequestRedraw>,
+        delta_time: Duration,
+    ) -> Option<(DVec3, DQuat)> {
+        self.update_with_limits(
+            camera,
+            original_translation,
+            original_rotation,
+            projection,
+            projection_adapters,
+            redraw,
+            delta_time,
+            &mut None,
+        )
+    }
+
+    /// Update this [`EditorCam`]'s transform and projection, reporting the zoom limit that was
+    /// reached, if any.
+    #[allow(clippy::too_many_arguments)]
+    fn update_with_limits(
         &mut self,
         camera: &Camera,
         original_translation: &DVec3,
         original_rotation: &DQuat,
         mut projection: Mut<Projection>,
+        projection_adapters: &ProjectionAdapters,
         redraw: &mut MessageWriter<RequestRedraw>,
         delta_time: Duration,
+        limit_reached: &mut Option<ZoomLimit>,
     ) -> Option<(DVec3, DQuat)> {
         let mut new_translation = *original_translation;
         let mut new_rotation = *original_rotation;
         let (anchor, orbit, pan, zoom) = match &mut self.current_motion {
             CurrentMotion::Stationary => return None,
             CurrentMotion::Momentum {
-                ref mut velocity, ..
+                ref mut velocity,
+                momentum_start,
             } => {
-                velocity.decay(self.momentum, delta_time);
+                let elapsed = Instant::now()
+                    .saturating_duration_since(*momentum_start)
+                    .saturating_sub(delta_time);
+                let distance = velocity.decay(&self.momentum, elapsed, delta_time);
                 match velocity {
                     Velocity::None => {
                         self.current_motion = CurrentMotion::Stationary;
                         return None;
                     }
-                    Velocity::Orbit { anchor, velocity } => (anchor, *velocity, DVec2::ZERO, 0.0),
-                    Velocity::Pan { anchor, velocity } => (anchor, DVec2::ZERO, *velocity, 0.0),
+                    Velocity::Orbit { anchor, velocity } => (
+                        anchor,
+                        velocity.normalize_or_zero() * distance,
+                        DVec2::ZERO,
+                        0.0,
+                    ),
+                    Velocity::Pan { anchor, velocity } => (
+                        anchor,
+                        DVec2::ZERO,
+                        velocity.normalize_or_zero() * distance,
+                        0.0,
+                    ),
+                    Velocity::Zoom { anchor, velocity } => (
+                        anchor,
+                        DVec2::ZERO,
+                        DVec2::ZERO,
+                        velocity.signum() * distance,
+                    ),
                 }
             }
             CurrentMotion::UserControlled {
@@ -399,12 +507,17 @@ impl EditorCam {
                 motion_inputs,
             } => (
                 anchor,
-                motion_inputs.smooth_orbit_velocity() * self.sensitivity.orbit.as_dvec2(),
+                motion_inputs.smooth_orbit_velocity(),
                 motion_inputs.smooth_pan_velocity(),
-                motion_inputs.smooth_zoom_velocity() * self.sensitivity.zoom as f64,
+                motion_inputs.smooth_zoom_velocity(),
             ),
         };
 
+        // Sensitivity is applied to both user inputs and momentum.
+        let orbit = orbit * self.sensitivity.orbit.as_dvec2();
+        let pan = pan * self.sensitivity.pan.as_dvec2();
+        let zoom = zoom * self.sensitivity.zoom as f64;
+
         // If there is no motion, we will have already early-exited.
         redraw.write(RequestRedraw);
 
@@ -451,17 +564,30 @@ impl EditorCam {
                 };
                 offset
             }
-            Projection::Orthographic(ortho) => DVec2::new(-ortho.scale as f64, ortho.scale as f64),
-            Projection::Custom(_) => {
-                error_once!("Custom projections are not supported.");
-                return None;
+            Projection::Orthographic(ortho) => {
+                let Some(size) = super::zoom::ortho_length_per_pixel(camera, ortho) else {
+                    error!("Malformed camera");
+                    return None;
+                };
+                DVec2::new(-size.x, size.y)
+            }
+            Projection::Custom(custom) => {
+                let Some(adapter) = projection_adapters.get(custom) else {
+                    error_once!("Custom projection has no registered `ProjectionAdapter`.");
+                    return None;
+                };
+                let Some(offset) = adapter.view_offset_per_pixel(camera, anchor.z) else {
+                    error!("Malformed camera");
+                    return None;
+                };
+                offset
             }
         };
 
         let pan_translation_view_space = (pan * view_offset).extend(0.0);
 
         let size_at_anchor =
-            super::zoom::length_per_pixel_at_view_space_pos(camera, *anchor).unwrap_or(0.0);
+            super::zoom::length_per_pixel(camera, &projection, *anchor).unwrap_or(0.0);
 
         // I'm not sure why I created this mapping - maybe it was to prevent zooming through
         // surfaces if the user really whipped the mouse:
@@ -488,7 +614,68 @@ impl EditorCam {
             zoom
         };
 
+        let fov_zoom = matches!(*projection, Projection::Perspective(_))
+            && self.perspective.zoom_mode == PerspectiveZoomMode::Fov;
+        let zooms_through_limits = self.zoom_limits.zoom_through_objects
+            && matches!(*projection, Projection::Perspective(_))
+            && !fov_zoom;
+        if zoom_bounded != zoom && !zooms_through_limits {
+            *limit_reached = Some(if zoom > 0.0 {
+                ZoomLimit::MinSizePerPixel
+            } else {
+                ZoomLimit::MaxSizePerPixel
+            });
+        }
+
         let zoom_translation_view_space = match &mut *projection {
+            Projection::Perspective(perspective) if fov_zoom => {
+                // Narrow or widen the field of view by the same fraction the camera would have
+                // moved toward the anchor, so both modes zoom at the same rate.
+                let fraction = (zoom_bounded * size_at_anchor
+                    / (anchor.length() * perspective.fov as f64))
+                    .min(0.9);
+                let half_fov_tan = (perspective.fov as f64 / 2.0).tan();
+                let min_half_fov_tan = (self.zoom_limits.min_fov as f64 / 2.0).tan();
+                let max_half_fov_tan = (self.zoom_limits.max_fov as f64 / 2.0).tan();
+                let new_half_fov_tan = (half_fov_tan * (1.0 - fraction))
+                    .clamp(min_half_fov_tan, max_half_fov_tan.max(min_half_fov_tan));
+                let at_fov_limit = zoom != 0.0 && new_half_fov_tan == half_fov_tan;
+                if at_fov_limit {
+                    *limit_reached = Some(if zoom > 0.0 {
+                        ZoomLimit::MinFov
+                    } else {
+                        ZoomLimit::MaxFov
+                    });
+                }
+
+                // Rotate the camera so the anchor stays at the same position on screen with the
+                // new field of view.
+                let aspect = perspective.aspect_ratio as f64;
+                let ndc = anchor.truncate() / (-anchor.z * DVec2::new(aspect, 1.0) * half_fov_tan);
+                let direction = (ndc * DVec2::new(aspect, 1.0) * new_half_fov_tan)
+                    .extend(-1.0)
+                    .normalize();
+                let correction = DQuat::from_rotation_arc(direction, anchor.normalize());
+                if new_half_fov_tan.is_finite() && correction.is_finite() && !at_fov_limit {
+                    let anchor_direction_world = new_rotation * *anchor;
+                    new_rotation *= correction;
+                    if let OrbitConstraint::Fixed { up, .. } = self.orbit_constraint {
+                        // Remove the roll introduced by the correction.
+                        let forward = cam_forward(new_rotation);
+                        if forward.cross(up).length() > 1e-6 {
+                            let up = if cam_up(new_rotation).dot(up) >= 0.0 {
+                                up
+                            } else {
+                                -up
+                            };
+                            new_rotation = look_to(forward, up);
+                        }
+                    }
+                    *anchor = new_rotation.inverse() * anchor_direction_world;
+                    perspective.fov = (new_half_fov_tan.atan() * 2.0) as f32;
+                }
+                DVec3::ZERO
+            }
             Projection::Perspective(perspective) => {
                 let zoom_amount = if self.zoom_limits.zoom_through_objects {
                     // Clamp the zoom speed at the limits
@@ -501,22 +688,76 @@ impl EditorCam {
                     zoom_bounded * size_at_anchor
                 };
                 // Scale this with the perspective FOV, so the zoom speed feels the same regardless.
-                anchor.normalize() * zoom_amount / perspective.fov as f64
+                let mut zoom_distance = zoom_amount / perspective.fov as f64;
+
+                // Bound the distance to the anchor, only preventing motion toward the violated
+                // limit, so a camera already past a limit is not pushed back.
+                let distance = anchor.length();
+                if let Some(min) = self.zoom_limits.min_distance {
+                    if zoom_distance > 0.0 && distance - zoom_distance < min {
+                        zoom_distance = (distance - min).max(0.0);
+                        *limit_reached = Some(ZoomLimit::MinDistance);
+                    }
+                }
+                if let Some(max) = self.zoom_limits.max_distance {
+                    if zoom_distance < 0.0 && distance - zoom_distance > max {
+                        zoom_distance = (distance - max).min(0.0);
+                        *limit_reached = Some(ZoomLimit::MaxDistance);
+                    }
+                }
+                let mut translation = anchor.normalize() * zoom_distance;
+
+                // Bound the altitude of the camera above the plane.
+                if let Some(altitude) = &self.zoom_limits.altitude {
+                    let normal = altitude.normal.normalize_or_zero();
+                    let current = (new_translation - altitude.point).dot(normal);
+                    let change = (new_rotation * translation).dot(normal);
+                    let bounded = match (altitude.min, altitude.max) {
+                        (Some(min), _) if change < 0.0 && current + change < min => {
+                            Some(((current - min) / -change, ZoomLimit::MinAltitude))
+                        }
+                        (_, Some(max)) if change > 0.0 && current + change > max => {
+                            Some(((max - current) / change, ZoomLimit::MaxAltitude))
+                        }
+                        _ => None,
+                    };
+                    if let Some((factor, limit)) = bounded {
+                        translation *= factor.clamp(0.0, 1.0);
+                        *limit_reached = Some(limit);
+                    }
+                }
+                translation
             }
             Projection::Orthographic(ref mut ortho) => {
                 // Constants are hand-tuned to feel equivalent between perspective and ortho. Might
                 // be a better way to do this correctly if it matters.
-                ortho.scale *= 1.0 - zoom_bounded as f32 * 0.0015;
-                // We don't move the camera in z, as this is managed by another ortho system.
-                anchor.normalize()
-                    * zoom_bounded
-                    * anchor.z.abs()
-                    * 0.0015
-                    * DVec3::new(1.0, 1.0, 0.0)
+                let fraction = zoom_bounded * ORTHO_ZOOM_PER_INPUT;
+                let scale = ortho.scale;
+                let mut new_scale = scale * (1.0 - fraction as f32);
+                if let Some(min) = self.zoom_limits.min_ortho_scale {
+                    if new_scale < scale && new_scale < min {
+                        new_scale = min.min(scale);
+                        *limit_reached = Some(ZoomLimit::MinOrthoScale);
+                    }
+                }
+                if let Some(max) = self.zoom_limits.max_ortho_scale {
+                    if new_scale > scale && new_scale > max {
+                        new_scale = max.max(scale);
+                        *limit_reached = Some(ZoomLimit::MaxOrthoScale);
+                    }
+                }
+                ortho.scale = new_scale;
+                // Move toward the anchor by the same fraction the view shrinks by, so the anchor
+                // stays under the pointer. We don't move the camera in z, as this is managed by
+                // another ortho system.
+                DVec3::new(anchor.x, anchor.y, 0.0) * (1.0 - new_scale as f64 / scale as f64)
             }
-            Projection::Custom(_) => {
-                error_once!("Custom projections are not supported.");
-                return None;
+            Projection::Custom(ref mut custom) => {
+                let Some(adapter) = projection_adapters.get_mut(custom) else {
+                    error_once!("Custom projection has no registered `ProjectionAdapter`.");
+                    return None;
+                };
+                adapter.zoom(camera, *anchor, zoom_bounded)?
             }
         };
 
@@ -526,6 +767,7 @@ impl EditorCam {
         if self.zoom_limits.zoom_through_objects
             && size_at_anchor < self.zoom_limits.min_size_per_pixel
             && matches!(*projection, Projection::Perspective(_))
+            && !fov_zoom
             && zoom > 0.0
         {
             *anchor += zoom_translation_view_space;
@@ -544,7 +786,7 @@ impl EditorCam {
             .mul_vec3(orbit_dir.cross(DVec3::NEG_Z).normalize())
             .normalize();
 
-        let orbit_multiplier = 0.005;
+        let orbit_multiplier = self.sensitivity.orbit_speed.radians_per_pixel(camera);
         if orbit.is_finite() && orbit.length() != 0.0 {
             match self.orbit_constraint {
                 OrbitConstraint::Fixed { up, can_pass_tdc } => {
@@ -617,6 +859,18 @@ impl EditorCam {
         }
 
         self.last_anchor_depth = anchor.z;
+
+        // Zoom momentum should not continue pushing against the zoom limits.
+        if limit_reached.is_some() {
+            if let CurrentMotion::Momentum {
+                velocity: ref mut velocity @ Velocity::Zoom { .. },
+                ..
+            } = self.current_motion
+            {
+                *velocity = Velocity::None;
+            }
+        }
+
         let (_, delta_rotation, delta_translation) = {
             let original =
                 DAffine3::from_rotation_translation(*original_rotation, *original_translation);
@@ -639,6 +893,30 @@ impl EditorCam {
         super::zoom::length_per_pixel_at_view_space_pos(camera, anchor_view)
     }
 
+    /// The fraction of the distance to the anchor the camera moves for one unit of zoom input,
+    /// including [`Sensitivity::zoom`]. For orthographic projections, this is the fraction the
+    /// projection scale shrinks by, and with [`PerspectiveZoomMode::Fov`], the fraction the
+    /// tangent of half the field of view shrinks by. Positive inputs zoom in.
+    ///
+    /// This is useful to convert a desired relative zoom into zoom inputs. Returns `None` for
+    /// custom projections, or if the camera is malformed.
+    pub fn zoom_fraction_per_input(&self, camera: &Camera, projection: &Projection) -> Option<f64> {
+        let fraction = match projection {
+            Projection::Perspective(perspective) => {
+                let anchor = self.anchor_view_space().unwrap_or(DVec3::new(
+                    0.0,
+                    0.0,
+                    self.last_anchor_depth(),
+                ));
+                let size_at_anchor = super::zoom::length_per_pixel(camera, projection, anchor)?;
+                size_at_anchor / (anchor.length() * perspective.fov as f64)
+            }
+            Projection::Orthographic(_) => ORTHO_ZOOM_PER_INPUT,
+            Projection::Custom(_) => return None,
+        } * self.sensitivity.zoom as f64;
+        (fraction.is_finite() && fraction != 0.0).then_some(fraction)
+    }
+
     /// The last known anchor depth. This value will always be negative.
     pub fn last_anchor_depth(&self) -> f64 {
         -self.last_anchor_depth.abs()
@@ -678,6 +956,10 @@ pub fn cam_up(cam_rotation: DQuat) -> DVec3 {
 
 /// Settings that define how camera orbit behaves.
 #[derive(Debug, Clone, Copy, Reflect)]
+#[cfg_attr(
+    feature = "extension_bookmarks",
+    derive(serde::Serialize, serde::Deserialize)
+)]
 pub enum OrbitConstraint {
     /// The camera's up direction is fixed.
     Fixed {
@@ -701,19 +983,102 @@ impl Default for OrbitConstraint {
 }
 
 /// The sensitivity of the camera controller to inputs.
+///
+/// Sensitivities are multiplied with inputs, and apply to both user inputs and momentum. Negative
+/// values invert the direction of the input.
 #[derive(Debug, Clone, Copy, Reflect)]
 pub struct Sensitivity {
     /// X/Y sensitivity of orbit inputs, multiplied.
     pub orbit: Vec2,
+    /// X/Y sensitivity of pan inputs, multiplied.
+    ///
+    /// A value of `1.0` is pixel-perfect: the point under the pointer stays under the pointer, as
+    /// if grabbing the world. Use `-1.0` to move the camera in the direction of the pointer
+    /// instead.
+    pub pan: Vec2,
     /// Sensitivity of zoom inputs, multiplied.
     pub zoom: f32,
+    /// How far the camera rotates for a given orbit input, before applying [`Sensitivity::orbit`].
+    pub orbit_speed: OrbitSpeed,
 }
 
 impl Default for Sensitivity {
     fn default() -> Self {
         Self {
             orbit: Vec2::splat(1.0),
+            pan: Vec2::splat(1.0),
             zoom: 1.0,
+            orbit_speed: Default::default(),
+        }
+    }
+}
+
+impl Sensitivity {
+    /// Invert the vertical orbit direction.
+    pub fn with_inverted_orbit_y(self) -> Self {
+        Self {
+            orbit: self.orbit * Vec2::new(1.0, -1.0),
+            ..self
+        }
+    }
+
+    /// Invert the horizontal orbit direction.
+    pub fn with_inverted_orbit_x(self) -> Self {
+        Self {
+            orbit: self.orbit * Vec2::new(-1.0, 1.0),
+            ..self
+        }
+    }
+
+    /// Invert the pan direction, moving the camera with the pointer instead of grabbing the world.
+    pub fn with_inverted_pan(self) -> Self {
+        Self {
+            pan: -self.pan,
+            ..self
+        }
+    }
+
+    /// Invert the zoom direction.
+    pub fn with_inverted_zoom(self) -> Self {
+        Self {
+            zoom: -self.zoom,
+            ..self
+        }
+    }
+}
+
+/// Defines how screen space orbit inputs are converted into camera rotation.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum OrbitSpeed {
+    /// The camera rotates by a fixed angle in radians for every logical pixel of input. The same
+    /// drag distance will rotate the camera by the same amount, regardless of the size of the
+    /// viewport.
+    RadiansPerPixel(f64),
+    /// The camera makes one full turn when the input moves across this fraction of the viewport.
+    /// The smaller of the viewport's logical width and height is used, so a drag across a small
+    /// inset viewport rotates the camera as much as the same relative drag across a large one.
+    ///
+    /// Because this is measured in logical pixels, it is unaffected by the window scale factor.
+    ViewportFractionPerTurn(f64),
+}
+
+impl Default for OrbitSpeed {
+    fn default() -> Self {
+        Self::RadiansPerPixel(0.005)
+    }
+}
+
+impl OrbitSpeed {
+    /// The rotation in radians per logical pixel of orbit input for this camera.
+    pub fn radians_per_pixel(&self, camera: &Camera) -> f64 {
+        match *self {
+            OrbitSpeed::RadiansPerPixel(radians) => radians,
+            OrbitSpeed::ViewportFractionPerTurn(fraction) => camera
+                .logical_viewport_size()
+                .map(|size| size.min_element() as f64 * fraction)
+                .filter(|length| *length > 0.0 && length.is_finite())
+                .map(|length| std::f64::consts::TAU / length)
+                .unwrap_or(Self::default().radians_per_pixel(camera)),
         }
     }
 }
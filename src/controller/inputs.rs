@@ -72,13 +72,14 @@ impl MotionInputs {
         }
     }
 
-    /// Approximate orbit velocity over the last `window`. to use for momentum calculations.
+    /// Approximate orbit velocity per second over the last `window`. to use for momentum
+    /// calculations.
     pub fn orbit_momentum(&self, window: Duration) -> DVec2 {
         if let Self::OrbitZoom {
             screenspace_inputs, ..
         } = self
         {
-            let velocity = screenspace_inputs.average_smoothed_value(window).as_dvec2();
+            let velocity = screenspace_inputs.smoothed_rate(window).as_dvec2();
             if !velocity.is_finite() {
                 DVec2::ZERO
             } else {
@@ -89,13 +90,14 @@ impl MotionInputs {
         }
     }
 
-    /// Approximate pan velocity over the last `window`. to use for momentum calculations.
+    /// Approximate pan velocity per second over the last `window`. to use for momentum
+    /// calculations.
     pub fn pan_momentum(&self, window: Duration) -> DVec2 {
         if let Self::PanZoom {
             screenspace_inputs, ..
         } = self
         {
-            let velocity = screenspace_inputs.average_smoothed_value(window).as_dvec2();
+            let velocity = screenspace_inputs.smoothed_rate(window).as_dvec2();
             if !velocity.is_finite() {
                 DVec2::ZERO
             } else {
@@ -106,6 +108,17 @@ impl MotionInputs {
         }
     }
 
+    /// Approximate zoom velocity per second over the last `window`. to use for momentum
+    /// calculations.
+    pub fn zoom_momentum(&self, window: Duration) -> f64 {
+        let velocity = self.zoom_inputs().smoothed_rate(window) as f64;
+        if !velocity.is_finite() {
+            0.0
+        } else {
+            velocity
+        }
+    }
+
     /// Motion-conserving smoothed zoom input velocity.
     pub fn smooth_zoom_velocity(&self) -> f64 {
         let velocity = self.zoom_inputs().latest_smoothed().unwrap_or(0.0) as f64;
@@ -7,7 +7,9 @@ pub mod component;
 pub mod inputs;
 pub mod momentum;
 pub mod motion;
+pub mod projection_adapter;
 pub mod projections;
+pub mod ray_cast;
 pub mod smoothing;
 pub mod transform_adapter;
 pub mod zoom;
@@ -20,6 +22,8 @@ pub struct MinimalEditorCamPlugin;
 impl Plugin for MinimalEditorCamPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<transform_adapter::TransformAdapter>()
+            .init_resource::<projection_adapter::ProjectionAdapters>()
+            .add_message::<zoom::ZoomLimitReached>()
             .add_systems(
                 PreUpdate,
                 (
@@ -29,10 +33,39 @@ impl Plugin for MinimalEditorCamPlugin {
                     // position, but the other two systems above do, so I'm putting
                     // them all in the SyncCameraPosition group.
                     crate::controller::projections::update_perspective,
+                    crate::controller::projections::update_custom,
                 )
                     .chain()
                     .after(bevy_picking::PickingSystems::Last)
                     .in_set(crate::SyncCameraPosition),
+            )
+            // Cache the parents of cameras before any system moves the cameras, and again after
+            // transform propagation, for systems that move cameras later in the frame.
+            .configure_sets(
+                PreUpdate,
+                crate::SyncCameraParents.before(crate::SyncCameraPosition),
+            )
+            .configure_sets(
+                PostUpdate,
+                crate::SyncCameraParents.after(bevy_transform::TransformSystems::Propagate),
             );
+        app.add_systems(
+            PreUpdate,
+            transform_adapter::ParentGlobalTransform::update.in_set(crate::SyncCameraParents),
+        )
+        .add_systems(
+            PostUpdate,
+            transform_adapter::ParentGlobalTransform::update.in_set(crate::SyncCameraParents),
+        );
+
+        #[cfg(feature = "big_space")]
+        app.add_systems(
+            PreUpdate,
+            transform_adapter::ParentGrid::update.in_set(crate::SyncCameraParents),
+        )
+        .add_systems(
+            PostUpdate,
+            transform_adapter::ParentGrid::update.in_set(crate::SyncCameraParents),
+        );
     }
 }
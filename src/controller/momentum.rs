@@ -1,59 +1,230 @@
 //! Provides the [`Momentum`] settings.
 
-use std::time::Duration;
+use std::{f64::consts::LN_2, time::Duration};
 
 use bevy_math::{DVec2, DVec3};
 use bevy_reflect::prelude::*;
 
 /// Defines momentum behavior of this [`super::component::EditorCam`].
+///
+/// Momentum velocities are measured in input units per second: logical pixels for pan and orbit,
+/// and zoom input units for zoom. The stopping distance of a flick can be found with
+/// [`MomentumDecay::stopping_distance`], which is useful for tuning how momentum feels.
+///
+/// Previous versions measured velocities per frame, and configured decay with `damping` and
+/// `friction` values. The default [`MomentumDecay::DragFriction`] is equivalent to the previous
+/// defaults at 60 frames per second: a damping of `160` is a half-life of 111ms, and a friction of
+/// `0.2` is a deceleration of 480 pixels per second squared. Unlike before, momentum now travels
+/// the same distance regardless of the framerate.
 #[derive(Debug, Clone, Copy, Reflect)]
 pub struct Momentum {
-    /// Momentum decay scales with velocity.
-    pub pan_damping: u8,
-    /// Momentum decay is constant.
-    pub pan_friction: f64,
+    /// How pan momentum decays.
+    pub pan: MomentumDecay,
     /// The sampling window to use when a movement ends to determine the velocity of the camera when
     /// momentum decay begins. The higher this value, the easier it is to "flick" the camera, but
     /// the more of a velocity discontinuity will be present when momentum starts.
     pub init_pan: Duration,
-    /// Momentum decay scales with velocity.
-    pub orbit_damping: u8,
-    /// Momentum decay is constant.
-    pub orbit_friction: f64,
+    /// How orbit momentum decays.
+    pub orbit: MomentumDecay,
     /// The sampling window to use when a movement ends to determine the velocity of the camera when
     /// momentum decay begins. The higher this value, the easier it is to "flick" the camera, but
     /// the more of a velocity discontinuity will be present when momentum starts.
     pub init_orbit: Duration,
+    /// How zoom momentum decays.
+    pub zoom: MomentumDecay,
+    /// The sampling window to use when a movement ends to determine the velocity of the camera when
+    /// momentum decay begins. The higher this value, the easier it is to "flick" the camera, but
+    /// the more of a velocity discontinuity will be present when momentum starts.
+    pub init_zoom: Duration,
 }
 
 impl Default for Momentum {
     fn default() -> Self {
         Self {
-            pan_damping: 160,
-            pan_friction: 0.2,
+            pan: Default::default(),
             init_pan: Duration::from_millis(40),
-            orbit_damping: 160,
-            orbit_friction: 0.2,
+            orbit: Default::default(),
             init_orbit: Duration::from_millis(60),
+            zoom: Default::default(),
+            init_zoom: Duration::from_millis(40),
+        }
+    }
+}
+
+/// Defines how the speed of the camera decays over time once the user has stopped moving it.
+pub trait DecayModel {
+    /// Returns the speed after `delta_time` has passed, given the current `speed`. The `elapsed`
+    /// time since momentum started is provided for models that are not only a function of speed.
+    fn decay(&self, speed: f64, elapsed: Duration, delta_time: Duration) -> f64;
+
+    /// The distance the camera will travel before stopping, if momentum starts with
+    /// `initial_speed`.
+    fn stopping_distance(&self, initial_speed: f64) -> f64;
+}
+
+/// Built-in momentum decay models. See [`CustomDecay`] to implement your own.
+#[derive(Debug, Clone, Copy, Reflect)]
+pub enum MomentumDecay {
+    /// No momentum, the camera stops as soon as inputs end.
+    None,
+    /// The speed halves every `half_life`.
+    ///
+    /// Stopping distance: `initial_speed * half_life / ln(2)`.
+    Exponential {
+        /// The time it takes for the speed to halve.
+        half_life: Duration,
+    },
+    /// The speed decreases at a constant rate, like an object sliding with friction.
+    ///
+    /// Stopping distance: `initial_speed² / (2 * deceleration)`.
+    ConstantDeceleration {
+        /// The rate at which speed decreases, in units per second squared.
+        deceleration: f64,
+    },
+    /// A combination of [`MomentumDecay::Exponential`] and
+    /// [`MomentumDecay::ConstantDeceleration`]. Fast flicks are slowed down quickly by the
+    /// exponential drag, while the constant deceleration ensures the camera comes to a stop in
+    /// finite time.
+    ///
+    /// Stopping distance: `v / k - (deceleration / k²) * ln(1 + k * v / deceleration)`, where `v`
+    /// is the initial speed, and `k = ln(2) / half_life`.
+    DragFriction {
+        /// The time it takes for the drag alone to halve the speed.
+        half_life: Duration,
+        /// The constant rate at which speed decreases, in units per second squared.
+        deceleration: f64,
+    },
+    /// The camera is slowed down by a critically damped spring. Unlike exponential decay, the
+    /// deceleration starts at zero, so there is no discontinuity in acceleration when momentum
+    /// starts, and the camera eases into its stopping point.
+    ///
+    /// Stopping distance: `2 * initial_speed / angular_frequency`.
+    CriticallyDampedSpring {
+        /// The natural angular frequency of the spring, in radians per second. Larger values stop
+        /// the camera more quickly.
+        angular_frequency: f64,
+    },
+    /// A user-defined decay model.
+    Custom(#[reflect(ignore)] CustomDecay),
+}
+
+impl Default for MomentumDecay {
+    fn default() -> Self {
+        Self::DragFriction {
+            half_life: Duration::from_millis(111),
+            deceleration: 480.0,
+        }
+    }
+}
+
+impl MomentumDecay {
+    /// Create a [`MomentumDecay::Custom`] from user-defined functions. See [`CustomDecay`].
+    pub fn custom(
+        decay: fn(f64, Duration, Duration) -> f64,
+        stopping_distance: fn(f64) -> f64,
+    ) -> Self {
+        Self::Custom(CustomDecay {
+            decay,
+            stopping_distance,
+        })
+    }
+}
+
+impl DecayModel for MomentumDecay {
+    fn decay(&self, speed: f64, elapsed: Duration, delta_time: Duration) -> f64 {
+        let dt = delta_time.as_secs_f64();
+        let new_speed = match self {
+            MomentumDecay::None => 0.0,
+            MomentumDecay::Exponential { half_life } => {
+                speed * (-dt / half_life.as_secs_f64()).exp2()
+            }
+            MomentumDecay::ConstantDeceleration { deceleration } => speed - deceleration * dt,
+            MomentumDecay::DragFriction {
+                half_life,
+                deceleration,
+            } => {
+                let k = LN_2 / half_life.as_secs_f64();
+                if k > 0.0 && k.is_finite() {
+                    (speed + deceleration / k) * (-k * dt).exp() - deceleration / k
+                } else {
+                    speed - deceleration * dt
+                }
+            }
+            MomentumDecay::CriticallyDampedSpring { angular_frequency } => {
+                let w = *angular_frequency;
+                let falloff = |t: f64| (1.0 + w * t) * (-w * t).exp();
+                let t = elapsed.as_secs_f64();
+                let initial_speed = speed / falloff(t);
+                initial_speed * falloff(t + dt)
+            }
+            MomentumDecay::Custom(model) => model.decay(speed, elapsed, delta_time),
+        };
+        if new_speed.is_finite() {
+            new_speed.max(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn stopping_distance(&self, initial_speed: f64) -> f64 {
+        let v = initial_speed.abs();
+        match self {
+            MomentumDecay::None => 0.0,
+            MomentumDecay::Exponential { half_life } => v * half_life.as_secs_f64() / LN_2,
+            MomentumDecay::ConstantDeceleration { deceleration } => v * v / (2.0 * deceleration),
+            MomentumDecay::DragFriction {
+                half_life,
+                deceleration,
+            } => {
+                let k = LN_2 / half_life.as_secs_f64();
+                if *deceleration <= 0.0 {
+                    v / k
+                } else if k > 0.0 && k.is_finite() {
+                    v / k - deceleration / (k * k) * (k * v / deceleration).ln_1p()
+                } else {
+                    v * v / (2.0 * deceleration)
+                }
+            }
+            MomentumDecay::CriticallyDampedSpring { angular_frequency } => {
+                2.0 * v / angular_frequency
+            }
+            MomentumDecay::Custom(model) => model.stopping_distance(v),
+        }
+    }
+}
+
+/// A user-defined decay model, used by [`MomentumDecay::Custom`]. The model is made of plain
+/// functions, so [`Momentum`] settings can be copied.
+#[derive(Debug, Clone, Copy)]
+pub struct CustomDecay {
+    /// Returns the speed after a time step, given the current speed, the time elapsed since
+    /// momentum started, and the duration of the step. See [`DecayModel::decay`].
+    pub decay: fn(f64, Duration, Duration) -> f64,
+    /// Returns the distance the camera will travel before stopping, given the initial speed. See
+    /// [`DecayModel::stopping_distance`].
+    pub stopping_distance: fn(f64) -> f64,
+}
+
+impl Default for CustomDecay {
+    fn default() -> Self {
+        Self {
+            decay: |speed, elapsed, delta_time| {
+                MomentumDecay::default().decay(speed, elapsed, delta_time)
+            },
+            stopping_distance: |initial_speed| {
+                MomentumDecay::default().stopping_distance(initial_speed)
+            },
         }
     }
 }
 
-impl Momentum {
-    fn decay_velocity_orbit(self, velocity: DVec2, delta_time: Duration) -> DVec2 {
-        let speed = velocity.length();
-        let f_damping = self.orbit_damping as f64 / 256.0 * speed * 10.0;
-        let f_friction = self.orbit_friction * 40.0;
-        let braking = (f_damping + f_friction) * delta_time.as_secs_f64();
-        (speed - braking).max(0.0) * velocity.normalize_or_zero()
+impl DecayModel for CustomDecay {
+    fn decay(&self, speed: f64, elapsed: Duration, delta_time: Duration) -> f64 {
+        (self.decay)(speed, elapsed, delta_time)
     }
 
-    fn decay_velocity_pan(self, velocity: DVec2, delta_time: Duration) -> DVec2 {
-        let speed = velocity.length();
-        let f_damping = self.pan_damping as f64 / 256.0 * speed * 10.0;
-        let f_friction = self.pan_friction * 40.0;
-        let braking = (f_damping + f_friction) * delta_time.as_secs_f64();
-        (speed - braking).max(0.0) * velocity.normalize_or_zero()
+    fn stopping_distance(&self, initial_speed: f64) -> f64 {
+        (self.stopping_distance)(initial_speed)
     }
 }
 
@@ -67,40 +238,155 @@ pub enum Velocity {
     Orbit {
         /// The anchor of rotation being orbited about.
         anchor: DVec3,
-        /// The current velocity of the camera about the anchor.
+        /// The current velocity of the camera about the anchor, in logical pixels per second.
         velocity: DVec2,
     },
     /// Camera is sliding.
     Pan {
         /// The anchor point that should stick to the pointer during panning.
         anchor: DVec3,
-        /// The current panning velocity of the camera.
+        /// The current panning velocity of the camera, in logical pixels per second.
         velocity: DVec2,
     },
+    /// Camera is zooming.
+    Zoom {
+        /// The anchor point being zoomed into.
+        anchor: DVec3,
+        /// The current zoom velocity of the camera, in zoom input units per second.
+        velocity: f64,
+    },
 }
 
 impl Velocity {
-    const DECAY_THRESHOLD: f64 = 1e-3;
-    /// Decay the velocity based on the momentum setting.
-    pub fn decay(&mut self, momentum: Momentum, delta_time: Duration) {
-        let is_none = match self {
-            Velocity::None => true,
+    /// Velocities are per second, this is the previous threshold of `1e-3` per frame at 60 frames
+    /// per second.
+    const DECAY_THRESHOLD: f64 = 0.06;
+
+    /// Decay the velocity based on the momentum setting. The `elapsed` time is the time since
+    /// momentum started, before this step.
+    ///
+    /// Returns the distance traveled during this step, in the direction of the velocity.
+    pub fn decay(&mut self, momentum: &Momentum, elapsed: Duration, delta_time: Duration) -> f64 {
+        let (speed, new_speed) = match self {
+            Velocity::None => (0.0, 0.0),
             Velocity::Orbit {
                 ref mut velocity, ..
             } => {
-                *velocity = momentum.decay_velocity_orbit(*velocity, delta_time);
-                velocity.length() <= Self::DECAY_THRESHOLD
+                let speed = velocity.length();
+                let new_speed = momentum.orbit.decay(speed, elapsed, delta_time);
+                *velocity = velocity.normalize_or_zero() * new_speed;
+                (speed, new_speed)
             }
             Velocity::Pan {
                 ref mut velocity, ..
             } => {
-                *velocity = momentum.decay_velocity_pan(*velocity, delta_time);
-                velocity.length() <= Self::DECAY_THRESHOLD
+                let speed = velocity.length();
+                let new_speed = momentum.pan.decay(speed, elapsed, delta_time);
+                *velocity = velocity.normalize_or_zero() * new_speed;
+                (speed, new_speed)
+            }
+            Velocity::Zoom {
+                ref mut velocity, ..
+            } => {
+                let speed = velocity.abs();
+                let new_speed = momentum.zoom.decay(speed, elapsed, delta_time);
+                *velocity = velocity.signum() * new_speed;
+                (speed, new_speed)
             }
         };
 
-        if is_none {
+        if new_speed <= Self::DECAY_THRESHOLD {
             *self = Velocity::None;
         }
+
+        // Integrate using the average speed over the step, so the distance traveled does not
+        // depend on the framerate.
+        (speed + new_speed) / 2.0 * delta_time.as_secs_f64()
+    }
+
+    /// The remaining distance the camera will travel before stopping, in the same units as the
+    /// velocity. For decay models that depend on the elapsed time, this treats the current speed as
+    /// the initial speed.
+    pub fn stopping_distance(&self, momentum: &Momentum) -> f64 {
+        match self {
+            Velocity::None => 0.0,
+            Velocity::Orbit { velocity, .. } => momentum.orbit.stopping_distance(velocity.length()),
+            Velocity::Pan { velocity, .. } => momentum.pan.stopping_distance(velocity.length()),
+            Velocity::Zoom { velocity, .. } => momentum.zoom.stopping_distance(velocity.abs()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: Duration = Duration::from_micros(100);
+
+    /// Integrate the motion of a decay model until it stops, using small time steps.
+    fn integrated_distance(model: &MomentumDecay, initial_speed: f64) -> f64 {
+        let dt = STEP;
+        let mut elapsed = Duration::ZERO;
+        let mut speed = initial_speed;
+        let mut distance = 0.0;
+        while speed > 1e-9 && elapsed < Duration::from_secs(60) {
+            let new_speed = model.decay(speed, elapsed, dt);
+            distance += (speed + new_speed) / 2.0 * dt.as_secs_f64();
+            speed = new_speed;
+            elapsed += dt;
+        }
+        distance
+    }
+
+    fn assert_stopping_distance(model: MomentumDecay) {
+        for initial_speed in [10.0, 500.0, 5000.0] {
+            let expected = model.stopping_distance(initial_speed);
+            let integrated = integrated_distance(&model, initial_speed);
+            // Allow for the error of a single integration step, where the model stops.
+            let tolerance = 1e-3 * expected + initial_speed * STEP.as_secs_f64();
+            assert!(
+                (expected - integrated).abs() <= tolerance,
+                "{model:?} at {initial_speed}: expected {expected}, integrated {integrated}"
+            );
+        }
+    }
+
+    #[test]
+    fn none_stops_immediately() {
+        assert_stopping_distance(MomentumDecay::None);
+    }
+
+    #[test]
+    fn exponential_stopping_distance() {
+        assert_stopping_distance(MomentumDecay::Exponential {
+            half_life: Duration::from_millis(150),
+        });
+    }
+
+    #[test]
+    fn constant_deceleration_stopping_distance() {
+        assert_stopping_distance(MomentumDecay::ConstantDeceleration {
+            deceleration: 800.0,
+        });
+    }
+
+    #[test]
+    fn drag_friction_stopping_distance() {
+        assert_stopping_distance(MomentumDecay::default());
+    }
+
+    #[test]
+    fn spring_stopping_distance() {
+        assert_stopping_distance(MomentumDecay::CriticallyDampedSpring {
+            angular_frequency: 8.0,
+        });
+    }
+
+    #[test]
+    fn custom_stopping_distance() {
+        assert_stopping_distance(MomentumDecay::custom(
+            |speed, _, delta_time| speed * (-2.0 * delta_time.as_secs_f64()).exp(),
+            |initial_speed| initial_speed / 2.0,
+        ));
     }
 }
@@ -0,0 +1,219 @@
+//! Defines how the camera controller works with [`Projection::Custom`] projections.
+//!
+//! Perspective and orthographic projections are supported out of the box. To use a custom
+//! projection, e.g. an off-axis or oblique projection, implement [`ProjectionAdapter`] for it, and
+//! register it with the [`ProjectionAdapters`] resource:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy::camera::{CameraProjection, SubCameraView};
+//! # use bevy_editor_cam::prelude::*;
+//! # use bevy::math::{DVec2, DVec3, Vec3A};
+//! #[derive(Debug, Clone)]
+//! struct MyProjection(PerspectiveProjection);
+//!
+//! # impl CameraProjection for MyProjection {
+//! #     fn get_clip_from_view(&self) -> Mat4 { self.0.get_clip_from_view() }
+//! #     fn get_clip_from_view_for_sub(&self, s: &SubCameraView) -> Mat4 { self.0.get_clip_from_view_for_sub(s) }
+//! #     fn update(&mut self, w: f32, h: f32) { self.0.update(w, h) }
+//! #     fn far(&self) -> f32 { self.0.far() }
+//! #     fn get_frustum_corners(&self, n: f32, f: f32) -> [Vec3A; 8] { self.0.get_frustum_corners(n, f) }
+//! # }
+//! impl ProjectionAdapter for MyProjection {
+//!     fn view_offset_per_pixel(&self, camera: &Camera, depth: f64) -> Option<DVec2> {
+//!         // ...
+//! #       None
+//!     }
+//!
+//!     fn zoom(&mut self, camera: &Camera, anchor: DVec3, zoom: f64) -> Option<DVec3> {
+//!         // ...
+//! #       None
+//!     }
+//!
+//!     fn update_clip_planes(&mut self, editor_cam: &EditorCam) -> f64 {
+//!         // ...
+//! #       0.0
+//!     }
+//! }
+//!
+//! # let mut app = App::new();
+//! app.add_plugins(DefaultEditorCamPlugins);
+//! app.world_mut()
+//!     .resource_mut::<ProjectionAdapters>()
+//!     .register::<MyProjection>();
+//! ```
+
+use std::{any::TypeId, fmt::Debug};
+
+use bevy_camera::{prelude::*, CameraProjection, CustomProjection};
+use bevy_ecs::prelude::*;
+use bevy_math::{DVec2, DVec3};
+
+use super::component::EditorCam;
+
+/// Implement this for a custom [`CameraProjection`] to allow the [`EditorCam`] to control a camera
+/// using it.
+pub trait ProjectionAdapter {
+    /// How far does the camera need to move in view space, at the given view space `depth`, so that
+    /// a point at that depth follows the pointer as it moves by one logical pixel? The `depth` will
+    /// be negative, as the camera looks down the negative z axis.
+    ///
+    /// Screen space inputs have their origin in the top left of the viewport, so for a typical
+    /// projection this will return a value with a negative `x` and a positive `y`. For example, an
+    /// orthographic projection using [`bevy_camera::ScalingMode::WindowSize`] returns
+    /// `(-scale, scale)`.
+    fn view_offset_per_pixel(&self, camera: &Camera, depth: f64) -> Option<DVec2>;
+
+    /// Apply a zoom input to this projection, returning the translation of the camera in view
+    /// space that should be applied to zoom toward the view space `anchor`.
+    ///
+    /// The `zoom` input is positive when zooming in, and has already been bounded by the camera's
+    /// [`ZoomLimits`](super::zoom::ZoomLimits). It has the same scale as the input sent to
+    /// [`EditorCam::send_zoom_input`], multiplied by the zoom sensitivity.
+    fn zoom(&mut self, camera: &Camera, anchor: DVec3, zoom: f64) -> Option<DVec3>;
+
+    /// Update the clipping planes of this projection, called every frame. Returns the distance the
+    /// camera should be moved forward along its view axis, which is useful for projections that
+    /// need to move the camera to keep the anchor inside the view frustum, like the orthographic
+    /// projection does. The anchor will be updated to account for this movement.
+    fn update_clip_planes(&mut self, editor_cam: &EditorCam) -> f64;
+
+    /// The vertical field of view of a perspective projection that would show the same area as this
+    /// projection at the given view space `depth`. Used to transition to and from this projection
+    /// with a dolly zoom.
+    fn equivalent_fov(&self, camera: &Camera, depth: f64) -> Option<f64> {
+        let height = camera.logical_viewport_size()?.y as f64;
+        let half_height = self.view_offset_per_pixel(camera, depth)?.y.abs() * height / 2.0;
+        let fov = 2.0 * (half_height / depth.abs()).atan();
+        (fov.is_finite() && fov > 0.0).then_some(fov)
+    }
+}
+
+/// Resource that holds the [`ProjectionAdapter`]s for all registered custom projections.
+///
+/// A [`Projection::Custom`] is type-erased, so the projection type must be registered here for the
+/// controller to find its [`ProjectionAdapter`] implementation.
+#[derive(Resource, Default)]
+pub struct ProjectionAdapters {
+    adapters: Vec<RegisteredAdapter>,
+}
+
+struct RegisteredAdapter {
+    type_id: TypeId,
+    get: fn(&CustomProjection) -> Option<&dyn ProjectionAdapter>,
+    get_mut: fn(&mut CustomProjection) -> Option<&mut dyn ProjectionAdapter>,
+}
+
+impl ProjectionAdapters {
+    /// Register a custom projection type, so the controller can use its [`ProjectionAdapter`].
+    /// Registering a type again replaces its existing registration.
+    pub fn register<P>(&mut self) -> &mut Self
+    where
+        P: ProjectionAdapter + CameraProjection + Debug + Send + Sync + Clone + 'static,
+    {
+        let registered = RegisteredAdapter {
+            type_id: TypeId::of::<P>(),
+            get: |custom| custom.get::<P>().map(|p| p as &dyn ProjectionAdapter),
+            get_mut: |custom| {
+                custom
+                    .get_mut::<P>()
+                    .map(|p| p as &mut dyn ProjectionAdapter)
+            },
+        };
+        match self
+            .adapters
+            .iter_mut()
+            .find(|adapter| adapter.type_id == registered.type_id)
+        {
+            Some(existing) => *existing = registered,
+            None => self.adapters.push(registered),
+        }
+        self
+    }
+
+    /// Is the custom projection type `P` registered?
+    pub fn is_registered<P: 'static>(&self) -> bool {
+        self.adapters
+            .iter()
+            .any(|adapter| adapter.type_id == TypeId::of::<P>())
+    }
+
+    /// Get the [`ProjectionAdapter`] of this custom projection, if its type has been registered.
+    pub fn get<'a>(&self, custom: &'a CustomProjection) -> Option<&'a dyn ProjectionAdapter> {
+        self.adapters
+            .iter()
+            .find_map(|adapter| (adapter.get)(custom))
+    }
+
+    /// Get the [`ProjectionAdapter`] of this custom projection mutably, if its type has been
+    /// registered.
+    pub fn get_mut<'a>(
+        &self,
+        custom: &'a mut CustomProjection,
+    ) -> Option<&'a mut dyn ProjectionAdapter> {
+        let adapter = self
+            .adapters
+            .iter()
+            .find(|adapter| (adapter.get)(custom).is_some())?;
+        (adapter.get_mut)(custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::SubCameraView;
+    use bevy_math::{Mat4, Vec3A};
+
+    use super::*;
+
+    #[derive(Debug, Clone, Default)]
+    struct TestProjection(PerspectiveProjection);
+
+    impl CameraProjection for TestProjection {
+        fn get_clip_from_view(&self) -> Mat4 {
+            self.0.get_clip_from_view()
+        }
+        fn get_clip_from_view_for_sub(&self, sub_view: &SubCameraView) -> Mat4 {
+            self.0.get_clip_from_view_for_sub(sub_view)
+        }
+        fn update(&mut self, width: f32, height: f32) {
+            self.0.update(width, height)
+        }
+        fn far(&self) -> f32 {
+            self.0.far()
+        }
+        fn get_frustum_corners(&self, z_near: f32, z_far: f32) -> [Vec3A; 8] {
+            self.0.get_frustum_corners(z_near, z_far)
+        }
+    }
+
+    impl ProjectionAdapter for TestProjection {
+        fn view_offset_per_pixel(&self, _: &Camera, _: f64) -> Option<DVec2> {
+            None
+        }
+        fn zoom(&mut self, _: &Camera, _: DVec3, _: f64) -> Option<DVec3> {
+            None
+        }
+        fn update_clip_planes(&mut self, _: &EditorCam) -> f64 {
+            0.0
+        }
+    }
+
+    #[test]
+    fn register_twice_replaces() {
+        let mut adapters = ProjectionAdapters::default();
+        assert!(!adapters.is_registered::<TestProjection>());
+        adapters
+            .register::<TestProjection>()
+            .register::<TestProjection>();
+        assert!(adapters.is_registered::<TestProjection>());
+        assert_eq!(adapters.adapters.len(), 1);
+
+        assert!(adapters.get(&CustomProjection::default()).is_none());
+        let Projection::Custom(mut custom) = Projection::custom(TestProjection::default()) else {
+            unreachable!();
+        };
+        assert!(adapters.get(&custom).is_some());
+        assert!(adapters.get_mut(&mut custom).is_some());
+    }
+}
@@ -4,6 +4,7 @@ use bevy_camera::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_math::{DQuat, DVec3};
 use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
 
 use crate::prelude::*;
 
@@ -30,6 +31,8 @@ pub struct PerspectiveSettings {
     /// clipped. Some parts of the object may protrude toward the camera, which is what necessitates
     /// this.
     pub near_clip_multiplier: f32,
+    /// How zoom inputs change the view of a perspective camera.
+    pub zoom_mode: PerspectiveZoomMode,
 }
 
 impl Default for PerspectiveSettings {
@@ -37,20 +40,37 @@ impl Default for PerspectiveSettings {
         Self {
             near_clip_limits: 1e-9..f32::INFINITY,
             near_clip_multiplier: 0.05,
+            zoom_mode: Default::default(),
         }
     }
 }
 
+/// How zoom inputs change the view of a perspective camera.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Reflect)]
+pub enum PerspectiveZoomMode {
+    /// Move the camera toward or away from the anchor.
+    #[default]
+    Dolly,
+    /// Keep the camera in place, and narrow or widen the field of view, like a telephoto lens. The
+    /// camera is rotated so the anchor stays fixed on screen. The field of view is bounded by
+    /// [`ZoomLimits::min_fov`] and [`ZoomLimits::max_fov`].
+    ///
+    /// [`ZoomLimits::min_fov`]: super::zoom::ZoomLimits::min_fov
+    /// [`ZoomLimits::max_fov`]: super::zoom::ZoomLimits::max_fov
+    Fov,
+}
+
 /// Updates perspective projection properties of editor cameras.
-pub fn update_perspective(mut cameras: Query<(&EditorCam, Mut<Projection>)>) {
-    for (editor_cam, mut projection) in cameras.iter_mut() {
+pub fn update_perspective(mut cameras: Query<(&EditorCam, &GlobalTransform, Mut<Projection>)>) {
+    for (editor_cam, transform, mut projection) in cameras.iter_mut() {
         let Projection::Perspective(ref mut perspective) = *projection else {
             continue;
         };
         let limits = editor_cam.perspective.near_clip_limits.clone();
         let multiplier = editor_cam.perspective.near_clip_multiplier;
-        perspective.near = (editor_cam.last_anchor_depth.abs() as f32 * multiplier)
+        let near = (editor_cam.last_anchor_depth.abs() as f32 * multiplier)
             .clamp(limits.start, limits.end);
+        perspective.near = near / EditorCam::depth_scale(transform) as f32;
     }
 }
 
@@ -58,14 +78,14 @@ pub fn update_perspective(mut cameras: Query<(&EditorCam, Mut<Projection>)>) {
 #[derive(Debug, Clone, Reflect)]
 pub struct OrthographicSettings {
     /// The camera's near clipping plane will move closer and farther from the anchor point during
-    /// zoom to maximize precision. The position of the near plane is based on the orthographic
-    /// projection `scale`, multiplied by this value.
+    /// zoom to maximize precision. The position of the near plane is based on the size of a pixel
+    /// in view space, multiplied by this value. With the default `ScalingMode::WindowSize`, this
+    /// is the orthographic projection `scale`.
     ///
     /// To maximize depth precision, make this as small ap possible. If the value is too large,
     /// depth-based effects like SSAO will break down. If the value is too small, objects that
-    /// should be visible will be clipped. Ideally, the clipping planes should scale with the scene
-    /// geometry and camera frustum to tightly bound the visible scene, but this is not yet
-    /// implemented.
+    /// should be visible will be clipped. To fit the clipping planes tightly around the visible
+    /// scene instead, see the [`clip_planes`](crate::extensions::clip_planes) extension.
     pub scale_to_near_clip: f32,
     /// Limits the distance the near clip plane can be to the anchor. The low limit is useful to
     /// prevent geometry clipping when zooming in, while the high limit is useful to prevent the
@@ -91,7 +111,13 @@ impl Default for OrthographicSettings {
 /// Update the ortho camera projection and position based on the [`OrthographicSettings`].
 pub fn update_orthographic(
     mut camera_set: ParamSet<(
-        Query<(Entity, &mut EditorCam, Mut<Projection>)>,
+        Query<(
+            Entity,
+            &mut EditorCam,
+            &Camera,
+            &GlobalTransform,
+            Mut<Projection>,
+        )>,
         Query<EntityMut, With<EditorCam>>,
     )>,
     transform_adapter: Res<TransformAdapter>,
@@ -99,38 +125,89 @@ pub fn update_orthographic(
     camera_set
         .p0()
         .iter_mut()
-        .filter_map(|(entity, mut editor_cam, mut projection)| {
-            if let Projection::Orthographic(ref mut orthographic) = *projection {
-                let mut delta_translation = DVec3::ZERO;
-                let anchor_dist = editor_cam.last_anchor_depth().abs() as f32;
-                let target_dist = (editor_cam.orthographic.scale_to_near_clip * orthographic.scale)
-                    .clamp(
-                        editor_cam.orthographic.near_clip_limits.start,
-                        editor_cam.orthographic.near_clip_limits.end,
-                    );
-
-                let forward_amount = anchor_dist - target_dist;
-                let cam_forward = DVec3::NEG_Z;
-                let movement = cam_forward * forward_amount as f64;
-
-                if movement != DVec3::ZERO {
-                    delta_translation += movement;
-                }
+        .filter_map(
+            |(entity, mut editor_cam, camera, transform, mut projection)| {
+                if let Projection::Orthographic(ref mut orthographic) = *projection {
+                    let mut delta_translation = DVec3::ZERO;
+                    let anchor_dist = editor_cam.last_anchor_depth().abs() as f32;
+                    // Distances from the controller are in world units, but the projection is in the
+                    // scaled view space of the camera.
+                    let depth_scale = EditorCam::depth_scale(transform) as f32;
+                    // The size of a pixel is the same as the scale for `ScalingMode::WindowSize`, this
+                    // makes the near clip behave the same for every scaling mode.
+                    let pixel_size = super::zoom::ortho_length_per_pixel(camera, orthographic)
+                        .map(|size| size.max_element() as f32)
+                        .unwrap_or(orthographic.scale)
+                        * depth_scale;
+                    let target_dist = (editor_cam.orthographic.scale_to_near_clip * pixel_size)
+                        .clamp(
+                            editor_cam.orthographic.near_clip_limits.start,
+                            editor_cam.orthographic.near_clip_limits.end,
+                        );
+
+                    let forward_amount = anchor_dist - target_dist;
+                    let cam_forward = DVec3::NEG_Z;
+                    let movement = cam_forward * forward_amount as f64;
+
+                    if movement != DVec3::ZERO {
+                        delta_translation += movement;
+                    }
 
-                editor_cam.last_anchor_depth += forward_amount as f64;
-                if let CurrentMotion::UserControlled { ref mut anchor, .. } =
-                    editor_cam.current_motion
-                {
-                    anchor.z += forward_amount as f64;
+                    editor_cam.last_anchor_depth += forward_amount as f64;
+                    if let CurrentMotion::UserControlled { ref mut anchor, .. } =
+                        editor_cam.current_motion
+                    {
+                        anchor.z += forward_amount as f64;
+                    }
+
+                    orthographic.near = 0.0;
+                    orthographic.far = anchor_dist
+                        * (1.0 + editor_cam.orthographic.far_clip_multiplier)
+                        / depth_scale;
+                    Some((entity, delta_translation))
+                } else {
+                    None
                 }
+            },
+        )
+        .collect::<Vec<_>>()
+        .iter()
+        .for_each(|(entity, delta_translation)| {
+            if let Ok(mut entity_mut) = camera_set.p1().get_mut(*entity) {
+                transform_adapter.apply_delta(&mut entity_mut, *delta_translation, DQuat::IDENTITY);
+            }
+        });
+}
+
+/// Update the clipping planes and position of cameras using a [`Projection::Custom`], using the
+/// registered [`ProjectionAdapter`].
+pub fn update_custom(
+    mut camera_set: ParamSet<(
+        Query<(Entity, &mut EditorCam, Mut<Projection>)>,
+        Query<EntityMut, With<EditorCam>>,
+    )>,
+    transform_adapter: Res<TransformAdapter>,
+    projection_adapters: Res<ProjectionAdapters>,
+) {
+    camera_set
+        .p0()
+        .iter_mut()
+        .filter_map(|(entity, mut editor_cam, mut projection)| {
+            let Projection::Custom(ref mut custom) = *projection else {
+                return None;
+            };
+            let adapter = projection_adapters.get_mut(custom)?;
+            let forward_amount = adapter.update_clip_planes(&editor_cam);
+            if forward_amount == 0.0 || !forward_amount.is_finite() {
+                return None;
+            }
 
-                orthographic.near = 0.0;
-                orthographic.far =
-                    anchor_dist * (1.0 + editor_cam.orthographic.far_clip_multiplier);
-                Some((entity, delta_translation))
-            } else {
-                None
+            editor_cam.last_anchor_depth += forward_amount;
+            if let CurrentMotion::UserControlled { ref mut anchor, .. } = editor_cam.current_motion
+            {
+                anchor.z += forward_amount;
             }
+            Some((entity, DVec3::NEG_Z * forward_amount))
         })
         .collect::<Vec<_>>()
         .iter()
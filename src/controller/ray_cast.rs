@@ -0,0 +1,89 @@
+//! Casts rays into the scene for features that need to find geometry that is not under the
+//! pointer, like [collision avoidance](crate::extensions::collision) and
+//! [screen center anchors](crate::input::anchor::AnchorSource::ScreenCenter).
+//!
+//! The controller does not know anything about your scene geometry. A [`RayCaster`] either uses the
+//! mesh picking backend of `bevy_picking`, with the `mesh_picking` feature, or a ray casting system
+//! you provide.
+
+use bevy_ecs::{prelude::*, system::SystemId};
+use bevy_log::prelude::*;
+use bevy_math::DVec3;
+
+/// A ray cast into the scene on behalf of a camera, passed as the input to ray casting systems.
+#[derive(Debug, Clone, Copy)]
+pub struct CameraRay {
+    /// The camera that is requesting the ray cast. Useful for ignoring the camera's own geometry.
+    pub camera: Entity,
+    /// The origin of the ray.
+    pub origin: DVec3,
+    /// The normalized direction of the ray.
+    pub direction: DVec3,
+    /// Hits farther than this distance from the origin can be ignored.
+    pub max_distance: f64,
+}
+
+/// How rays are cast into the scene.
+#[derive(Debug, Clone, Copy)]
+pub enum RayCaster {
+    /// A registered system that is given a [`CameraRay`], and returns the distance to the nearest
+    /// hit along the ray, if any.
+    System(SystemId<In<CameraRay>, Option<f64>>),
+    /// Cast rays against meshes with the ray casting of the `bevy_picking` mesh backend. The ray
+    /// must be in world space. Meshes are hit if they are visible, whether or not they are
+    /// pickable.
+    #[cfg(feature = "mesh_picking")]
+    MeshPicking,
+}
+
+impl From<SystemId<In<CameraRay>, Option<f64>>> for RayCaster {
+    fn from(system: SystemId<In<CameraRay>, Option<f64>>) -> Self {
+        Self::System(system)
+    }
+}
+
+impl RayCaster {
+    /// Cast the `ray`, returning the distance to the nearest hit along the ray that is no farther
+    /// than [`CameraRay::max_distance`].
+    pub fn cast(&self, world: &mut World, ray: CameraRay) -> Option<f64> {
+        let hit = match self {
+            RayCaster::System(system) => match world.run_system_with(*system, ray) {
+                Ok(hit) => hit,
+                Err(err) => {
+                    error_once!("Unable to run the ray casting system: {err}");
+                    None
+                }
+            },
+            #[cfg(feature = "mesh_picking")]
+            RayCaster::MeshPicking => match world.run_system_cached_with(mesh_ray_cast, ray) {
+                Ok(hit) => hit,
+                Err(err) => {
+                    error_once!("Unable to run the mesh ray cast: {err}");
+                    None
+                }
+            },
+        };
+        hit.filter(|distance| {
+            distance.is_finite() && *distance >= 0.0 && *distance <= ray.max_distance
+        })
+    }
+}
+
+#[cfg(feature = "mesh_picking")]
+fn mesh_ray_cast(
+    In(ray): In<CameraRay>,
+    mut ray_cast: bevy_picking::mesh_picking::ray_cast::MeshRayCast,
+) -> Option<f64> {
+    use bevy_picking::mesh_picking::ray_cast::MeshRayCastSettings;
+
+    let direction = bevy_math::Dir3::new(ray.direction.as_vec3()).ok()?;
+    let ray3d = bevy_math::Ray3d::new(ray.origin.as_vec3(), direction);
+    let filter = |entity| entity != ray.camera;
+    let settings = MeshRayCastSettings::default()
+        .with_filter(&filter)
+        .always_early_exit();
+    ray_cast
+        .cast_ray(ray3d, &settings)
+        .first()
+        .map(|(_, hit)| hit.distance as f64)
+}
@@ -2,35 +2,101 @@
 
 use std::{
     collections::VecDeque,
-    ops::{Add, AddAssign, Mul},
+    f32::consts::TAU,
+    ops::{Add, AddAssign, Mul, Sub},
     time::Duration,
 };
 
 use bevy_derive::{Deref, DerefMut};
+use bevy_math::prelude::*;
 use bevy_platform::time::Instant;
 use bevy_reflect::prelude::*;
 
-/// How smooth should inputs be? Over what tine window should they be averaged.
+/// How smooth should inputs be? Which filter should be used for each kind of motion.
 #[derive(Debug, Clone, Copy, Reflect)]
 pub struct Smoothing {
-    /// Smoothing window for panning.
-    pub pan: Duration,
-    /// Smoothing window for orbit.
-    pub orbit: Duration,
-    /// Smoothing window for zoom.
-    pub zoom: Duration,
+    /// Smoothing filter for panning.
+    pub pan: SmoothingFilter,
+    /// Smoothing filter for orbit.
+    pub orbit: SmoothingFilter,
+    /// Smoothing filter for zoom.
+    pub zoom: SmoothingFilter,
 }
 
 impl Default for Smoothing {
     fn default() -> Self {
         Smoothing {
-            pan: Duration::from_millis(10),
-            orbit: Duration::from_millis(30),
-            zoom: Duration::from_millis(60),
+            pan: Duration::from_millis(10).into(),
+            orbit: Duration::from_millis(30).into(),
+            zoom: Duration::from_millis(60).into(),
         }
     }
 }
 
+/// The filter used to smooth an [`InputQueue`].
+///
+/// All filters are motion-conserving: the input that has not yet been output by the filter is
+/// tracked, and the filter always converges on it. This means smoothed pointer motions will arrive
+/// at the same destination as the unsmoothed inputs, no matter which filter is used. When the filter
+/// is changed, input that has not been output yet is carried over to the new filter.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum SmoothingFilter {
+    /// Averages inputs over a time window. Each input is spread evenly over the window, so it is
+    /// fully consumed once the window has passed.
+    MovingAverage(Duration),
+    /// The [One Euro filter](https://gery.casiez.net/1euro/). The cutoff frequency adapts to the
+    /// speed of the input, so slow motions are smooth and precise, while fast motions have low lag.
+    OneEuro {
+        /// The cutoff frequency in hertz used when the input is not moving. Lower values make slow
+        /// motions smoother, but add lag.
+        min_cutoff: f32,
+        /// How much the cutoff frequency increases with the speed of the input, in input units per
+        /// second. Higher values reduce lag during fast motions.
+        beta: f32,
+        /// The cutoff frequency in hertz used to filter the input speed.
+        derivative_cutoff: f32,
+    },
+    /// The smoothed output follows the input as if attached by a critically damped spring. This
+    /// results in motion that eases in and out without overshooting.
+    CriticallyDampedSpring {
+        /// The natural angular frequency of the spring, in radians per second. Larger values follow
+        /// the input more closely.
+        angular_frequency: f32,
+    },
+}
+
+impl From<Duration> for SmoothingFilter {
+    fn from(window: Duration) -> Self {
+        Self::MovingAverage(window)
+    }
+}
+
+/// Types that can be smoothed in an [`InputQueue`].
+pub trait Smoothable:
+    Copy + Default + Add<Output = Self> + AddAssign<Self> + Sub<Output = Self> + Mul<f32, Output = Self>
+{
+    /// The magnitude of this value, used by filters that adapt to the speed of the input.
+    fn magnitude(self) -> f32;
+}
+
+impl Smoothable for f32 {
+    fn magnitude(self) -> f32 {
+        self.abs()
+    }
+}
+
+impl Smoothable for Vec2 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+
+impl Smoothable for Vec3 {
+    fn magnitude(self) -> f32 {
+        self.length()
+    }
+}
+
 /// A smoothed queue of inputs over time.
 ///
 /// Useful for smoothing to query "what was the average input over the last N milliseconds?". This
@@ -41,6 +107,9 @@ impl Default for Smoothing {
 /// 2. The sum of smoothed and unsmoothed inputs will be equal despite (1). This is useful because
 ///    you can smooth something like pointer motions, and the smoothed output will arrive at the
 ///    same destination as the unsmoothed input without drifting.
+///
+/// The filter used to smooth inputs is chosen with a [`SmoothingFilter`] each time an input is
+/// processed.
 #[derive(Debug, Clone, Reflect, Deref, DerefMut)]
 pub struct InputQueue<T>(pub VecDeque<InputStreamEntry<T>>);
 
@@ -61,11 +130,20 @@ pub struct InputStreamEntry<T> {
     /// stream is always in a valid state, and the act of a user reading a sample multiple times
     /// does not change the value they get.
     smoothed_value: T,
+    /// The sum of all inputs that have not yet been output as smoothed values, including this one.
+    /// This is what makes every filter motion-conserving.
+    residual: T,
+    /// Filter state that tracks the rate of change. Used by the One Euro filter for the filtered
+    /// input speed, and by the spring filter for the velocity of the spring.
+    derivative: T,
+    /// Was this sample smoothed with [`SmoothingFilter::MovingAverage`]? That filter tracks the
+    /// input that has not been output with `fraction_remaining`, instead of the `residual`.
+    is_windowed: bool,
+    /// Was this sample added with [`InputQueue::process_unsmoothed_input`]?
+    is_unsmoothed: bool,
 }
 
-impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>> Default
-    for InputQueue<T>
-{
+impl<T: Smoothable> Default for InputQueue<T> {
     fn default() -> Self {
         let start = Instant::now();
         let interval = Duration::from_secs_f32(1.0 / 60.0);
@@ -84,21 +162,127 @@ impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>>
                 sample: T::default(),
                 fraction_remaining: 1.0,
                 smoothed_value: T::default(),
+                residual: T::default(),
+                derivative: T::default(),
+                is_windowed: true,
+                is_unsmoothed: false,
             })
         }
         Self(queue)
     }
 }
 
-impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>> InputQueue<T> {
+impl<T: Smoothable> InputQueue<T> {
     const MAX_EVENTS: usize = 256;
+    /// The residual of a filter is considered settled when it is smaller than this fraction of the
+    /// largest input in the queue.
+    const SETTLED_FRACTION: f32 = 1e-4;
 
-    /// Add an input sample to the queue, and compute the smoothed value.
+    /// Add an input sample to the queue, and compute the smoothed value using the `filter`.
     ///
     /// The smoothing must be computed at the time a sample is added to ensure no samples are over
     /// or under sampled in the smoothing process.
-    pub fn process_input(&mut self, new_input: T, smoothing: Duration) {
-        let now = Instant::now();
+    pub fn process_input(&mut self, new_input: T, filter: impl Into<SmoothingFilter>) {
+        self.process_input_at(Instant::now(), new_input, filter);
+    }
+
+    fn process_input_at(&mut self, now: Instant, new_input: T, filter: impl Into<SmoothingFilter>) {
+        let (last_time, residual, derivative, was_windowed) = self
+            .0
+            .front()
+            .map(|entry| {
+                let (time, residual) = (entry.time, entry.residual);
+                (time, residual, entry.derivative, entry.is_windowed)
+            })
+            .unwrap_or((now, T::default(), T::default(), true));
+        let dt = now
+            .saturating_duration_since(last_time)
+            .as_secs_f32()
+            .max(f32::EPSILON);
+        let target = residual + new_input;
+        let filter = filter.into();
+        let is_windowed = matches!(filter, SmoothingFilter::MovingAverage(_));
+        // When the filter changes, the input that has not been output yet is carried over to the
+        // new filter, so switching filters does not lose or repeat any input.
+        let sample = if is_windowed && !was_windowed {
+            // The moving average only outputs the samples in the queue, so the residual of the
+            // previous filter is averaged along with the new input.
+            new_input + residual
+        } else {
+            new_input
+        };
+        if !is_windowed && was_windowed {
+            // The part of each sample that the moving average has not output is already included
+            // in the residual, which is used by the other filters.
+            for entry in self.0.iter_mut() {
+                entry.fraction_remaining = 0.0;
+            }
+        }
+
+        let (smoothed_value, fraction_remaining, derivative) = match filter {
+            SmoothingFilter::MovingAverage(window) => {
+                let (smoothed_value, fraction_remaining) = self.moving_average(now, sample, window);
+                (smoothed_value, fraction_remaining, T::default())
+            }
+            SmoothingFilter::OneEuro {
+                min_cutoff,
+                beta,
+                derivative_cutoff,
+            } => {
+                let alpha = |cutoff: f32| 1.0 / (1.0 + 1.0 / (TAU * cutoff.max(f32::EPSILON) * dt));
+                let speed = new_input * (1.0 / dt);
+                let derivative = derivative + (speed - derivative) * alpha(derivative_cutoff);
+                let cutoff = min_cutoff + beta * derivative.magnitude();
+                (target * alpha(cutoff), 0.0, derivative)
+            }
+            SmoothingFilter::CriticallyDampedSpring { angular_frequency } => {
+                // The offset of the spring from the target, and the spring velocity. The target
+                // does not move during this step, so we can use the closed-form solution.
+                let w = angular_frequency;
+                let offset = target * -1.0;
+                let decay = (-w * dt).exp();
+                let c = derivative + offset * w;
+                let next_offset = (offset + c * dt) * decay;
+                let next_velocity = (derivative - c * (w * dt)) * decay;
+                (next_offset - offset, 0.0, next_velocity)
+            }
+        };
+
+        let residual = target - smoothed_value;
+        let queue = &mut self.0;
+        queue.truncate(Self::MAX_EVENTS - 1);
+        queue.push_front(InputStreamEntry {
+            time: now,
+            sample,
+            fraction_remaining,
+            smoothed_value,
+            residual,
+            derivative,
+            is_windowed,
+            is_unsmoothed: false,
+        })
+    }
+
+    /// Add an input sample that is output immediately, along with any input that has not been
+    /// output yet. Use this for inputs that have already been eased, and should not be smoothed
+    /// again.
+    ///
+    /// Unsmoothed samples are not included in the [`InputQueue::smoothed_rate`], so they do not
+    /// cause momentum.
+    pub fn process_unsmoothed_input(&mut self, new_input: T) {
+        self.process_unsmoothed_input_at(Instant::now(), new_input);
+    }
+
+    fn process_unsmoothed_input_at(&mut self, now: Instant, new_input: T) {
+        self.process_input_at(now, new_input, Duration::ZERO);
+        if let Some(entry) = self.0.front_mut() {
+            entry.is_unsmoothed = true;
+        }
+    }
+
+    /// Compute the smoothed value of `new_input` by averaging over the `smoothing` window. Returns
+    /// the smoothed value, and the fraction of the new input that remains to be consumed.
+    fn moving_average(&mut self, now: Instant, new_input: T, smoothing: Duration) -> (T, f32) {
         let queue = &mut self.0;
 
         // Compute the expected sampling window end index
@@ -133,13 +317,40 @@ impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>>
             old_entry.fraction_remaining = 0.0;
         }
 
-        queue.truncate(Self::MAX_EVENTS - 1);
-        queue.push_front(InputStreamEntry {
-            time: now,
-            sample: new_input,
-            fraction_remaining: 1.0 - target_fraction,
-            smoothed_value,
-        })
+        (smoothed_value, 1.0 - target_fraction)
+    }
+
+    /// The sum of all inputs that have not yet been output as smoothed values.
+    pub fn residual(&self) -> T {
+        self.0
+            .front()
+            .map(|entry| entry.residual)
+            .unwrap_or_default()
+    }
+
+    /// Returns `true` if the latest input was zero, and the filter has output all previous inputs.
+    pub fn is_settled(&self) -> bool {
+        let Some(latest) = self.0.front() else {
+            return true;
+        };
+        if latest.sample.magnitude() != 0.0 {
+            return false;
+        }
+        if latest.is_windowed {
+            // The moving average tracks exactly how much of each sample has been output.
+            return self
+                .0
+                .iter()
+                .all(|entry| entry.fraction_remaining <= 0.0 || entry.sample.magnitude() == 0.0);
+        }
+        // The residual is a running difference, so it accumulates rounding errors relative to the
+        // size of the inputs, and can not be compared to an absolute threshold.
+        let largest_input = self
+            .0
+            .iter()
+            .map(|entry| entry.sample.magnitude())
+            .fold(0.0, f32::max);
+        latest.residual.magnitude() <= Self::SETTLED_FRACTION * largest_input
     }
 
     /// Get the latest motion-conserving smoothed input value.
@@ -175,6 +386,18 @@ impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>>
         sum * (1.0 / count as f32)
     }
 
+    /// Approximate the rate of change of the smoothed values per second, over the `window`.
+    pub fn smoothed_rate(&self, window: Duration) -> T {
+        let now = Instant::now();
+        self.0
+            .iter()
+            .filter(|entry| !entry.is_unsmoothed && now.duration_since(entry.time) < window)
+            .map(|entry| entry.smoothed_value)
+            .reduce(|acc, v| acc + v)
+            .unwrap_or_default()
+            * (1.0 / window.as_secs_f32())
+    }
+
     /// Approximate smoothed value with user-supplied modifier function as needed
     pub fn approx_smoothed(&self, window: Duration, mut modifier: impl FnMut(&mut T)) -> T {
         let now = Instant::now();
@@ -192,3 +415,118 @@ impl<T: Copy + Default + Add<Output = T> + AddAssign<T> + Mul<f32, Output = T>>
             * (1.0 / *n_elements as f32)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_micros(16_667);
+
+    const INPUTS: [f32; 8] = [150.0, 150.0, 0.0, 300.0, 150.0, -150.0, 450.0, 150.0];
+
+    const SPRING: SmoothingFilter = SmoothingFilter::CriticallyDampedSpring {
+        angular_frequency: 30.0,
+    };
+
+    /// Feed `inputs` to a queue, one per frame, followed by enough zero inputs for the filter to
+    /// settle. Returns the sum of the smoothed outputs, and whether the queue settled.
+    fn run(filter: SmoothingFilter, inputs: &[f32]) -> (f32, bool) {
+        run_switching(filter, filter, inputs)
+    }
+
+    /// Like [`run`], but the inputs are smoothed with `first` for the first half of the inputs, and
+    /// `second` after that.
+    fn run_switching(
+        first: SmoothingFilter,
+        second: SmoothingFilter,
+        inputs: &[f32],
+    ) -> (f32, bool) {
+        let filter = second;
+        let mut queue = InputQueue::<f32>::default();
+        let mut now = Instant::now();
+        let mut output = 0.0;
+        for (i, &input) in inputs.iter().enumerate() {
+            now += FRAME;
+            let filter = if i < inputs.len() / 2 { first } else { second };
+            queue.process_input_at(now, input, filter);
+            output += queue.latest_smoothed().unwrap();
+            assert!(
+                !queue.is_settled(),
+                "{filter:?} settled while receiving inputs"
+            );
+        }
+        for _ in 0..240 {
+            now += FRAME;
+            queue.process_input_at(now, 0.0, filter);
+            output += queue.latest_smoothed().unwrap();
+            if queue.is_settled() {
+                return (output, true);
+            }
+        }
+        (output, false)
+    }
+
+    fn assert_conserves_and_settles(filter: SmoothingFilter) {
+        // Scroll wheel inputs, in pixels.
+        let total: f32 = INPUTS.iter().sum();
+        let (output, settled) = run(filter, &INPUTS);
+        assert!(settled, "{filter:?} did not settle");
+        assert!(
+            (output - total).abs() <= 1e-3 * total,
+            "{filter:?} output {output}, expected {total}"
+        );
+    }
+
+    #[test]
+    fn moving_average() {
+        assert_conserves_and_settles(Duration::from_millis(60).into());
+    }
+
+    #[test]
+    fn one_euro() {
+        assert_conserves_and_settles(SmoothingFilter::OneEuro {
+            min_cutoff: 1.0,
+            beta: 0.01,
+            derivative_cutoff: 1.0,
+        });
+    }
+
+    #[test]
+    fn critically_damped_spring() {
+        assert_conserves_and_settles(SPRING);
+    }
+
+    #[test]
+    fn switching_filters() {
+        let total: f32 = INPUTS.iter().sum();
+        let moving_average = Duration::from_millis(60).into();
+        for (first, second) in [(SPRING, moving_average), (moving_average, SPRING)] {
+            let (output, settled) = run_switching(first, second, &INPUTS);
+            assert!(settled, "{first:?} to {second:?} did not settle");
+            assert!(
+                (output - total).abs() <= 1e-3 * total,
+                "{first:?} to {second:?} output {output}, expected {total}"
+            );
+        }
+    }
+
+    #[test]
+    fn unsmoothed_input_outputs_residual() {
+        let mut queue = InputQueue::<f32>::default();
+        let mut now = Instant::now();
+        let mut output = 0.0;
+        for input in [150.0, 150.0] {
+            now += FRAME;
+            queue.process_input_at(now, input, SPRING);
+            output += queue.latest_smoothed().unwrap();
+        }
+        assert!(queue.residual() > 0.0);
+        now += FRAME;
+        queue.process_unsmoothed_input_at(now, 100.0);
+        output += queue.latest_smoothed().unwrap();
+        assert!(
+            (output - 400.0).abs() <= 1e-3,
+            "output {output}, expected 400"
+        );
+    }
+}
@@ -2,21 +2,38 @@
 //!
 //! By default, [`TransformAdapter`] reads and writes Bevy's built-in [`Transform`] component.
 //! To use a different transform representation (e.g. a 64-bit transform), replace this resource
-//! with custom `read` and `apply_delta` callbacks.
+//! with custom `read` and `apply_delta` callbacks. To use a different transform representation for
+//! a single camera, add a [`TransformAdapterOverride`] component to it.
+//!
+//! Adapter callbacks can only access the camera entity, so the controller copies the
+//! [`GlobalTransform`] of each camera's parent into its [`ParentGlobalTransform`].
+//!
+//! With the `big_space` feature, `TransformAdapter::big_space` provides an adapter for cameras
+//! in a `big_space` floating origin grid, and `ParentGrid` caches the grid of each camera.
+
+use std::sync::Arc;
 
 use bevy_ecs::prelude::*;
 use bevy_log::error_once;
-use bevy_math::{DQuat, DVec3};
+use bevy_math::{DMat4, DQuat, DVec3};
 use bevy_transform::prelude::*;
 
 /// Resource that defines how the camera controller reads and writes transform data.
 ///
 /// By default, this reads and writes Bevy's built-in [`Transform`] component. Replace this
 /// resource to use a different transform component (e.g. a 64-bit transform) via [`Self::new`].
-#[derive(Resource)]
+///
+/// The default adapter reads the camera's position in world space, and applies motions in world
+/// units, ignoring the scale of the camera. If the camera has a parent, motions are converted into
+/// the space of the parent, so cameras can be mounted on a moving rig. Parents should be scaled
+/// uniformly.
+///
+/// Entities with a [`TransformAdapterOverride`] component are read and written with the adapter
+/// in that component instead.
+#[derive(Resource, Clone)]
 pub struct TransformAdapter {
-    read_fn: Box<dyn Fn(&EntityRef) -> Option<(DVec3, DQuat)> + Send + Sync>,
-    apply_delta_fn: Box<dyn Fn(&mut EntityMut, DVec3, DQuat) + Send + Sync>,
+    read_fn: Arc<dyn Fn(&EntityRef) -> Option<(DVec3, DQuat)> + Send + Sync>,
+    apply_delta_fn: Arc<dyn Fn(&mut EntityMut, DVec3, DQuat) + Send + Sync>,
 }
 
 impl TransformAdapter {
@@ -26,49 +43,211 @@ impl TransformAdapter {
         apply_delta: impl Fn(&mut EntityMut, DVec3, DQuat) + Send + Sync + 'static,
     ) -> Self {
         Self {
-            read_fn: Box::new(read),
-            apply_delta_fn: Box::new(apply_delta),
+            read_fn: Arc::new(read),
+            apply_delta_fn: Arc::new(apply_delta),
         }
     }
 
-    /// Read the translation and rotation of an entity.
+    /// Read the translation and rotation of an entity, using the entity's
+    /// [`TransformAdapterOverride`] if it has one.
     pub fn read(&self, entity: &EntityRef) -> Option<(DVec3, DQuat)> {
-        (self.read_fn)(entity)
+        match entity.get::<TransformAdapterOverride>() {
+            Some(adapter) => (adapter.0.read_fn)(entity),
+            None => (self.read_fn)(entity),
+        }
     }
 
-    /// Apply a movement delta to an entity.
+    /// Apply a movement delta to an entity, using the entity's [`TransformAdapterOverride`] if it
+    /// has one.
     pub fn apply_delta(
         &self,
         entity: &mut EntityMut,
         delta_translation: DVec3,
         delta_rotation: DQuat,
     ) {
-        (self.apply_delta_fn)(entity, delta_translation, delta_rotation)
+        let apply_delta_fn = match entity.get::<TransformAdapterOverride>() {
+            Some(adapter) => adapter.0.apply_delta_fn.clone(),
+            None => self.apply_delta_fn.clone(),
+        };
+        apply_delta_fn(entity, delta_translation, delta_rotation)
     }
 }
 
+/// Optional. Overrides the [`TransformAdapter`] resource for this camera. This allows cameras with
+/// different transform representations in the same app, like a camera in a 64-bit world and a
+/// camera in a model preview using [`Transform`].
+#[derive(Component, Clone)]
+pub struct TransformAdapterOverride(pub TransformAdapter);
+
 impl Default for TransformAdapter {
     fn default() -> Self {
+        Self::new(read_transform, apply_delta_to_transform)
+    }
+}
+
+#[cfg(feature = "big_space")]
+impl TransformAdapter {
+    /// Create a `TransformAdapter` for cameras in a `big_space` [`Grid`](big_space::prelude::Grid).
+    ///
+    /// The camera position is read from its [`CellCoord`](big_space::prelude::CellCoord) and
+    /// [`Transform`], as a 64-bit position in the grid, so positions stay the same when the camera
+    /// moves to another cell and `big_space` recenters its [`Transform`]. Movement is applied to
+    /// the [`Transform`], and `big_space` moves the camera to the correct cell.
+    ///
+    /// The grid is the parent of the camera, which is copied to the camera's [`ParentGrid`] by
+    /// the controller.
+    pub fn big_space() -> Self {
         Self::new(
             |entity| {
-                let Some(cam_transform) = entity.get::<Transform>() else {
+                let Some(cell) = entity.get::<big_space::prelude::CellCoord>() else {
+                    error_once!("Unable to retrieve CellCoord from EditorCam entity.");
+                    return None;
+                };
+                let Some(transform) = entity.get::<Transform>() else {
                     error_once!("Unable to retrieve Transform from EditorCam entity.");
                     return None;
                 };
+                let Some(ParentGrid(Some(grid))) = entity.get::<ParentGrid>() else {
+                    error_once!("Unable to retrieve the Grid of the EditorCam entity's parent.");
+                    return None;
+                };
                 Some((
-                    cam_transform.translation.as_dvec3(),
-                    cam_transform.rotation.as_dquat(),
+                    grid.grid_position_double(cell, transform),
+                    transform.rotation.as_dquat(),
                 ))
             },
-            |entity, delta_translation, delta_rotation| {
-                let Some(mut cam_transform) = entity.get_mut::<Transform>() else {
-                    error_once!("Unable to retrieve Transform from EditorCam entity.");
-                    return;
-                };
-                let delta_transform = Transform::from_translation(delta_translation.as_vec3())
-                    .with_rotation(delta_rotation.as_quat());
-                *cam_transform = cam_transform.mul_transform(delta_transform);
-            },
+            apply_local_delta,
         )
     }
 }
+
+/// A copy of the [`Grid`](big_space::prelude::Grid) a camera is in, kept up to date by the
+/// controller. Adapter callbacks can only access the camera entity, so this allows
+/// `TransformAdapter::big_space` to read the grid of the camera's parent. This is `None` if the
+/// parent of the camera is not a grid.
+///
+/// This is required by [`EditorCam`](crate::prelude::EditorCam), and updated in the
+/// [`SyncCameraParents`](crate::SyncCameraParents) set.
+#[cfg(feature = "big_space")]
+#[derive(Component, Clone, Default)]
+pub struct ParentGrid(pub Option<big_space::prelude::Grid>);
+
+#[cfg(feature = "big_space")]
+impl ParentGrid {
+    /// Copy the grid of each camera's parent into its [`ParentGrid`].
+    pub fn update(
+        mut cameras: Query<(Option<&ChildOf>, &mut ParentGrid), With<crate::prelude::EditorCam>>,
+        grids: Query<&big_space::prelude::Grid>,
+    ) {
+        for (parent, mut cached) in &mut cameras {
+            let grid = parent.and_then(|parent| grids.get(parent.parent()).ok());
+            match (grid, &mut cached.0) {
+                (Some(grid), Some(cached)) => cached.clone_from(grid),
+                (Some(grid), cached @ None) => *cached = Some(grid.clone()),
+                (None, cached @ Some(_)) => *cached = None,
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+/// A copy of the [`GlobalTransform`] of a camera's parent, kept up to date by the controller.
+/// Adapter callbacks can only access the camera entity, so this allows the default adapter to
+/// convert motions into the space of the parent. This is `None` if the camera has no parent.
+///
+/// This is required by [`EditorCam`](crate::prelude::EditorCam), and updated in the
+/// [`SyncCameraParents`](crate::SyncCameraParents) set.
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
+pub struct ParentGlobalTransform(pub Option<GlobalTransform>);
+
+impl ParentGlobalTransform {
+    /// Copy the [`GlobalTransform`] of each camera's parent into its [`ParentGlobalTransform`].
+    pub fn update(
+        mut cameras: Query<
+            (Option<&ChildOf>, &mut ParentGlobalTransform),
+            With<crate::prelude::EditorCam>,
+        >,
+        parents: Query<&GlobalTransform>,
+    ) {
+        for (parent, mut cached) in &mut cameras {
+            let transform = parent.and_then(|parent| parents.get(parent.parent()).ok());
+            cached.set_if_neq(ParentGlobalTransform(transform.copied()));
+        }
+    }
+}
+
+/// Reads the world space translation and rotation of the camera, without scale.
+fn read_transform(entity: &EntityRef) -> Option<(DVec3, DQuat)> {
+    let Some(cam_transform) = entity.get::<Transform>() else {
+        error_once!("Unable to retrieve Transform from EditorCam entity.");
+        return None;
+    };
+    if parent_transform(entity).is_none() {
+        return Some((
+            cam_transform.translation.as_dvec3(),
+            cam_transform.rotation.as_dquat(),
+        ));
+    }
+    let (_, rotation, translation) = entity
+        .get::<GlobalTransform>()?
+        .to_scale_rotation_translation();
+    Some((translation.as_dvec3(), rotation.as_dquat()))
+}
+
+/// Moves the camera by a delta in its view space, in world units, and converts the result into the
+/// space of the camera's parent.
+fn apply_delta_to_transform(
+    entity: &mut EntityMut,
+    delta_translation: DVec3,
+    delta_rotation: DQuat,
+) {
+    let Some(parent) = parent_transform(&entity.as_readonly()) else {
+        apply_local_delta(entity, delta_translation, delta_rotation);
+        return;
+    };
+    let Some((translation, rotation)) = read_transform(&entity.as_readonly()) else {
+        return;
+    };
+    let translation = translation + rotation * delta_translation;
+    let rotation = (rotation * delta_rotation).normalize();
+
+    let Some(mut cam_transform) = entity.get_mut::<Transform>() else {
+        return;
+    };
+    let (_, parent_rotation, _) = parent.to_scale_rotation_translation();
+    cam_transform.translation = parent.inverse().transform_point3(translation).as_vec3();
+    cam_transform.rotation = (parent_rotation.inverse() * rotation).normalize().as_quat();
+    let local = *cam_transform;
+    // Keep the global transform in sync with the new local transform, so motions applied later in
+    // the same frame start from the new position. Transform propagation will overwrite this with
+    // the same value, unless the parent moves.
+    if let Some(mut global) = entity.get_mut::<GlobalTransform>() {
+        *global = GlobalTransform::from(parent.as_mat4()) * local;
+    }
+}
+
+/// Moves a camera without a parent by a delta in its view space, in world units.
+fn apply_local_delta(entity: &mut EntityMut, delta_translation: DVec3, delta_rotation: DQuat) {
+    let Some(mut cam_transform) = entity.get_mut::<Transform>() else {
+        error_once!("Unable to retrieve Transform from EditorCam entity.");
+        return;
+    };
+    // Unlike `Transform::mul_transform`, the translation is not multiplied by the scale of the
+    // camera, so the camera moves in world units.
+    let rotation = cam_transform.rotation.as_dquat();
+    cam_transform.translation += (rotation * delta_translation).as_vec3();
+    cam_transform.rotation = (rotation * delta_rotation).normalize().as_quat();
+}
+
+/// The world space transform of the camera's parent, read from its [`ParentGlobalTransform`].
+/// Until that is available, it is estimated from the camera's [`Transform`] and
+/// [`GlobalTransform`]. Returns `None` if the camera has no parent.
+fn parent_transform(entity: &EntityRef) -> Option<DMat4> {
+    entity.get::<ChildOf>()?;
+    if let Some(ParentGlobalTransform(Some(parent))) = entity.get::<ParentGlobalTransform>() {
+        return Some(parent.to_matrix().as_dmat4());
+    }
+    let global = entity.get::<GlobalTransform>()?.to_matrix().as_dmat4();
+    let local = entity.get::<Transform>()?.to_matrix().as_dmat4();
+    Some(global * local.inverse())
+}
@@ -1,6 +1,7 @@
 //! Provides [`ZoomLimits`] settings.
 
-use bevy_camera::prelude::*;
+use bevy_camera::{prelude::*, ScalingMode};
+use bevy_ecs::prelude::*;
 use bevy_math::{DVec2, DVec3};
 use bevy_reflect::Reflect;
 
@@ -33,6 +34,22 @@ pub struct ZoomLimits {
     /// Additionally, when reaching `max_size_per_pixel`, the camera does not continue zooming out,
     /// but instead continues at the same speed.
     pub zoom_through_objects: bool,
+    /// The narrowest vertical field of view in radians, when zooming a perspective camera with
+    /// [`PerspectiveZoomMode::Fov`](super::projections::PerspectiveZoomMode::Fov).
+    pub min_fov: f32,
+    /// The widest vertical field of view in radians, when zooming a perspective camera with
+    /// [`PerspectiveZoomMode::Fov`](super::projections::PerspectiveZoomMode::Fov).
+    pub max_fov: f32,
+    /// The closest a perspective camera can zoom toward the anchor, in world space units.
+    pub min_distance: Option<f64>,
+    /// The farthest a perspective camera can zoom away from the anchor, in world space units.
+    pub max_distance: Option<f64>,
+    /// Bounds the altitude of a perspective camera above a plane while zooming.
+    pub altitude: Option<AltitudeLimits>,
+    /// The smallest [`OrthographicProjection::scale`] when zooming in.
+    pub min_ortho_scale: Option<f32>,
+    /// The largest [`OrthographicProjection::scale`] when zooming out.
+    pub max_ortho_scale: Option<f32>,
 }
 
 impl Default for ZoomLimits {
@@ -41,10 +58,83 @@ impl Default for ZoomLimits {
             min_size_per_pixel: 1e-6, // Any smaller and floating point rendering artifacts appear.
             max_size_per_pixel: 1e27, // The diameter of the observable universe is probably a good upper limit.
             zoom_through_objects: false,
+            min_fov: 1f32.to_radians(),
+            max_fov: 120f32.to_radians(),
+            min_distance: None,
+            max_distance: None,
+            altitude: None,
+            min_ortho_scale: None,
+            max_ortho_scale: None,
         }
     }
 }
 
+/// Bounds the altitude of the camera above a plane while zooming, for example to keep the camera
+/// above the ground. Panning and orbiting are not bounded.
+///
+/// The plane is in the same space as the translation read by the
+/// [`TransformAdapter`](super::transform_adapter::TransformAdapter).
+#[derive(Debug, Clone, Reflect)]
+pub struct AltitudeLimits {
+    /// Any point on the plane.
+    pub point: DVec3,
+    /// The normal of the plane, pointing toward positive altitudes.
+    pub normal: DVec3,
+    /// The lowest altitude the camera can zoom to.
+    pub min: Option<f64>,
+    /// The highest altitude the camera can zoom to.
+    pub max: Option<f64>,
+}
+
+impl Default for AltitudeLimits {
+    fn default() -> Self {
+        Self {
+            point: DVec3::ZERO,
+            normal: DVec3::Y,
+            min: None,
+            max: None,
+        }
+    }
+}
+
+/// One of the limits defined in [`ZoomLimits`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+pub enum ZoomLimit {
+    /// [`ZoomLimits::min_size_per_pixel`]
+    MinSizePerPixel,
+    /// [`ZoomLimits::max_size_per_pixel`]
+    MaxSizePerPixel,
+    /// [`ZoomLimits::min_fov`]
+    MinFov,
+    /// [`ZoomLimits::max_fov`]
+    MaxFov,
+    /// [`ZoomLimits::min_distance`]
+    MinDistance,
+    /// [`ZoomLimits::max_distance`]
+    MaxDistance,
+    /// [`AltitudeLimits::min`]
+    MinAltitude,
+    /// [`AltitudeLimits::max`]
+    MaxAltitude,
+    /// [`ZoomLimits::min_ortho_scale`]
+    MinOrthoScale,
+    /// [`ZoomLimits::max_ortho_scale`]
+    MaxOrthoScale,
+}
+
+/// Sent when a zoom is first stopped or slowed by one of the [`ZoomLimits`] of a camera. This can
+/// be used to give the user feedback when they can't zoom any further.
+///
+/// This is not sent again while the camera is held against the same limit. It is sent again once
+/// the camera moves without reaching a limit, or reaches a different limit.
+#[derive(Debug, Clone, Message)]
+pub struct ZoomLimitReached {
+    /// The camera that reached the limit.
+    pub camera: Entity,
+    /// The limit that was reached.
+    pub limit: ZoomLimit,
+}
+
 /// The size of a pixel at the anchor (under the pointer) in world space units.
 ///
 /// This is a much better way to compute scale than using camera distance from the anchor (the
@@ -71,6 +161,84 @@ pub fn length_per_pixel_at_view_space_pos(camera: &Camera, view_space_pos: DVec3
     len_per_pixel.is_finite().then_some(len_per_pixel)
 }
 
+/// The size of a pixel at a point in view space, in world space units.
+///
+/// Unlike [`length_per_pixel_at_view_space_pos`], this uses the current state of the projection,
+/// instead of the camera's projection matrix, which is only updated once per frame. For
+/// orthographic projections, the horizontal size of a pixel is returned.
+pub fn length_per_pixel(
+    camera: &Camera,
+    projection: &Projection,
+    view_space_pos: DVec3,
+) -> Option<f64> {
+    match projection {
+        Projection::Orthographic(ortho) => ortho_length_per_pixel(camera, ortho).map(|size| size.x),
+        _ => length_per_pixel_at_view_space_pos(camera, view_space_pos),
+    }
+}
+
+/// The size of a logical pixel of an orthographic projection in view space units, for every
+/// [`ScalingMode`]. The horizontal and vertical sizes differ if the scaling mode stretches the
+/// view, like [`ScalingMode::Fixed`].
+pub fn ortho_length_per_pixel(camera: &Camera, ortho: &OrthographicProjection) -> Option<DVec2> {
+    let viewport = camera.logical_viewport_size()?.as_dvec2();
+    let size = ortho_unscaled_size(viewport, &ortho.scaling_mode) * ortho.scale as f64 / viewport;
+    size.is_finite().then_some(size)
+}
+
+/// The factor that converts the visible half height of an orthographic projection, in view space
+/// units, into its scale.
+pub(crate) fn ortho_tri_base_to_scale_factor(
+    camera: &Camera,
+    ortho: &OrthographicProjection,
+) -> f64 {
+    if let Some(size) = camera.logical_viewport_size() {
+        2.0 / ortho_unscaled_size(size.as_dvec2(), &ortho.scaling_mode).y
+    } else {
+        0.00278
+    }
+}
+
+/// The view space size of an orthographic projection with a scale of `1.0`, matching how `bevy`
+/// computes [`OrthographicProjection::area`].
+pub(crate) fn ortho_unscaled_size(viewport: DVec2, scaling_mode: &ScalingMode) -> DVec2 {
+    let (width, height) = (viewport.x, viewport.y);
+    match *scaling_mode {
+        ScalingMode::WindowSize => viewport,
+        ScalingMode::AutoMin {
+            min_width,
+            min_height,
+        } => {
+            let (min_width, min_height) = (min_width as f64, min_height as f64);
+            if width * min_height > min_width * height {
+                DVec2::new(width * min_height / height, min_height)
+            } else {
+                DVec2::new(min_width, height * min_width / width)
+            }
+        }
+        ScalingMode::AutoMax {
+            max_width,
+            max_height,
+        } => {
+            let (max_width, max_height) = (max_width as f64, max_height as f64);
+            if width * max_height < max_width * height {
+                DVec2::new(width * max_height / height, max_height)
+            } else {
+                DVec2::new(max_width, height * max_width / width)
+            }
+        }
+        ScalingMode::FixedVertical { viewport_height } => {
+            let viewport_height = viewport_height as f64;
+            DVec2::new(width * viewport_height / height, viewport_height)
+        }
+        ScalingMode::FixedHorizontal { viewport_width } => {
+            let viewport_width = viewport_width as f64;
+            DVec2::new(viewport_width, height * viewport_width / width)
+        }
+        ScalingMode::Fixed { width, height } => DVec2::new(width as f64, height as f64),
+    }
+}
+
 /// Project a point in view space onto the camera's viewport.
 fn view_to_viewport(camera: &Camera, view_space_point: DVec3) -> Option<DVec2> {
     let ndc_space_coords = camera
@@ -92,3 +260,94 @@ fn view_to_viewport(camera: &Camera, view_space_point: DVec3) -> Option<DVec2> {
     viewport_position.y = target_size.y - viewport_position.y;
     Some(viewport_position)
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::{CameraProjection, RenderTargetInfo};
+    use bevy_math::UVec2;
+
+    use super::*;
+
+    fn camera(width: u32, height: u32) -> Camera {
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(width, height),
+            scale_factor: 1.0,
+        });
+        camera
+    }
+
+    /// Compare the length per pixel against the area `bevy` computes for the projection, in
+    /// landscape and portrait viewports.
+    fn assert_matches_bevy(scaling_mode: ScalingMode) {
+        for (width, height) in [(1280, 720), (720, 1280)] {
+            let camera = camera(width, height);
+            let mut ortho = OrthographicProjection {
+                scaling_mode,
+                scale: 2.5,
+                ..OrthographicProjection::default_3d()
+            };
+            ortho.update(width as f32, height as f32);
+            let viewport = DVec2::new(width as f64, height as f64);
+            let expected = ortho.area.size().as_dvec2() / viewport;
+
+            let size = ortho_length_per_pixel(&camera, &ortho).unwrap();
+            assert!(
+                (size - expected).abs().max_element() <= 1e-6 * expected.max_element(),
+                "{scaling_mode:?} at {width}x{height}: {size}, expected {expected}"
+            );
+
+            let half_height = ortho.area.height() as f64 / 2.0;
+            let factor = ortho_tri_base_to_scale_factor(&camera, &ortho);
+            let scale = half_height * factor;
+            assert!(
+                (scale - ortho.scale as f64).abs() <= 1e-6,
+                "{scaling_mode:?} at {width}x{height}: scale {scale}, expected {}",
+                ortho.scale
+            );
+        }
+    }
+
+    #[test]
+    fn window_size() {
+        assert_matches_bevy(ScalingMode::WindowSize);
+    }
+
+    #[test]
+    fn fixed() {
+        assert_matches_bevy(ScalingMode::Fixed {
+            width: 10.0,
+            height: 4.0,
+        });
+    }
+
+    #[test]
+    fn auto_min() {
+        assert_matches_bevy(ScalingMode::AutoMin {
+            min_width: 16.0,
+            min_height: 12.0,
+        });
+    }
+
+    #[test]
+    fn auto_max() {
+        assert_matches_bevy(ScalingMode::AutoMax {
+            max_width: 16.0,
+            max_height: 12.0,
+        });
+    }
+
+    #[test]
+    fn fixed_vertical() {
+        assert_matches_bevy(ScalingMode::FixedVertical {
+            viewport_height: 8.0,
+        });
+    }
+
+    #[test]
+    fn fixed_horizontal() {
+        assert_matches_bevy(ScalingMode::FixedHorizontal {
+            viewport_width: 8.0,
+        });
+    }
+}
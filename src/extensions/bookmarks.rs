@@ -0,0 +1,531 @@
+//! A `bevy_editor_cam` extension that saves camera views as named bookmarks. Bookmarks can be
+//! recalled with a smooth transition, and saved to a RON file to share "standard views" of a model
+//! with others.
+//!
+//! Send a [`BookmarkTrigger`] to save or recall a bookmark. The bookmarks are stored in the
+//! [`Bookmarks`] resource, which can also be used to save and load them:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_editor_cam::extensions::bookmarks::*;
+//! fn save(bookmarks: Res<Bookmarks>) {
+//!     if let Err(err) = bookmarks.views.save("views.ron") {
+//!         error!("{err}");
+//!     }
+//! }
+//!
+//! fn load(mut bookmarks: ResMut<Bookmarks>) {
+//!     match BookmarkSet::load("views.ron") {
+//!         Ok(views) => bookmarks.views = views,
+//!         Err(err) => error!("{err}"),
+//!     }
+//! }
+//! ```
+
+use std::{collections::BTreeMap, fmt::Display, path::Path, time::Duration};
+
+use bevy_app::prelude::*;
+use bevy_camera::{prelude::*, ScalingMode};
+use bevy_ecs::prelude::*;
+use bevy_log::prelude::*;
+use bevy_math::{prelude::*, DQuat, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_window::RequestRedraw;
+use serde::{Deserialize, Serialize};
+
+use super::tween::{CameraTween, RotationPivot, TweenFov, TweenPlugin, TweenRotation, Tweens};
+use crate::{controller::zoom::ortho_tri_base_to_scale_factor, prelude::*};
+
+/// See the [module](self) docs.
+pub struct BookmarksPlugin;
+
+impl Plugin for BookmarksPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
+        app.init_resource::<Bookmarks>()
+            .add_message::<BookmarkTrigger>()
+            .add_systems(PostUpdate, BookmarkTrigger::receive);
+    }
+}
+
+/// Send this event to save or recall a view bookmark. Bookmarks are stored in the [`Bookmarks`]
+/// resource.
+#[derive(Debug, Clone, Message)]
+pub enum BookmarkTrigger {
+    /// Save the current view of the camera under this name, replacing any existing bookmark with
+    /// the same name.
+    Save {
+        /// The camera to capture the view of.
+        camera: Entity,
+        /// The name of the bookmark.
+        name: String,
+    },
+    /// Smoothly move the camera to the view saved under this name.
+    Recall {
+        /// The camera to move.
+        camera: Entity,
+        /// The name of the bookmark.
+        name: String,
+    },
+}
+
+impl BookmarkTrigger {
+    fn receive(
+        mut events: MessageReader<Self>,
+        mut state: ResMut<Bookmarks>,
+        mut tweens: ResMut<Tweens>,
+        mut cameras: Query<EntityMut, With<EditorCam>>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        for event in events.read() {
+            match event {
+                BookmarkTrigger::Save { camera, name } => {
+                    let Ok(camera) = cameras.get(*camera) else {
+                        continue;
+                    };
+                    if let Some(view) = ViewBookmark::capture(&camera, &transform_adapter) {
+                        state.views.insert(name.clone(), view);
+                    }
+                }
+                BookmarkTrigger::Recall { camera, name } => {
+                    let Some(view) = state.views.get(name).copied() else {
+                        error!("There is no view bookmark named \"{name}\".");
+                        continue;
+                    };
+                    let Ok(mut camera_mut) = cameras.get_mut(*camera) else {
+                        continue;
+                    };
+                    if state.transition_to(&mut tweens, &mut camera_mut, view, &transform_adapter) {
+                        redraw.write(RequestRedraw);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The projection of a camera, saved in a [`ViewBookmark`]. Settings that are computed from the
+/// viewport, like the aspect ratio, are not saved.
+#[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
+pub enum BookmarkProjection {
+    /// A perspective projection.
+    Perspective {
+        /// The vertical field of view, in radians.
+        fov: f32,
+        /// The distance to the near clipping plane.
+        near: f32,
+        /// The distance to the far clipping plane.
+        far: f32,
+    },
+    /// An orthographic projection.
+    Orthographic {
+        /// The scale of the projection.
+        scale: f32,
+        /// The distance to the near clipping plane.
+        near: f32,
+        /// The distance to the far clipping plane.
+        far: f32,
+        /// The origin of the viewport, see [`OrthographicProjection::viewport_origin`].
+        viewport_origin: Vec2,
+        /// How the projection scales to the viewport.
+        scaling_mode: ScalingMode,
+    },
+}
+
+impl BookmarkProjection {
+    /// Capture the parameters of this [`Projection`], if it is perspective or orthographic.
+    pub fn capture(projection: &Projection) -> Option<Self> {
+        match projection {
+            Projection::Perspective(perspective) => Some(Self::Perspective {
+                fov: perspective.fov,
+                near: perspective.near,
+                far: perspective.far,
+            }),
+            Projection::Orthographic(ortho) => Some(Self::Orthographic {
+                scale: ortho.scale,
+                near: ortho.near,
+                far: ortho.far,
+                viewport_origin: ortho.viewport_origin,
+                scaling_mode: ortho.scaling_mode,
+            }),
+            Projection::Custom(_) => None,
+        }
+    }
+
+    /// Set `projection` to this projection. If `projection` is already of the same kind, only the
+    /// saved parameters are changed, otherwise it is replaced.
+    pub fn apply(&self, projection: &mut Projection) {
+        match (self, projection) {
+            (Self::Perspective { fov, near, far }, Projection::Perspective(perspective)) => {
+                perspective.fov = *fov;
+                perspective.near = *near;
+                perspective.far = *far;
+            }
+            (
+                Self::Orthographic {
+                    scale,
+                    near,
+                    far,
+                    viewport_origin,
+                    scaling_mode,
+                },
+                Projection::Orthographic(ortho),
+            ) => {
+                ortho.scale = *scale;
+                ortho.near = *near;
+                ortho.far = *far;
+                ortho.viewport_origin = *viewport_origin;
+                ortho.scaling_mode = *scaling_mode;
+            }
+            (Self::Perspective { .. }, projection) => {
+                *projection = Projection::Perspective(PerspectiveProjection::default());
+                self.apply(projection);
+            }
+            (Self::Orthographic { .. }, projection) => {
+                *projection = Projection::Orthographic(OrthographicProjection::default_3d());
+                self.apply(projection);
+            }
+        }
+    }
+
+    /// Set the camera's projection to this projection, and configure `tween` to animate the field
+    /// of view or scale from the camera's current view.
+    ///
+    /// When the kind of projection changes, the camera switches projection immediately, with a
+    /// field of view or scale that keeps the view the same size at the anchor, so that the
+    /// transition is continuous at the anchor.
+    fn animate(&self, camera: &mut EntityMut, anchor_depth: f64, tween: &mut CameraTween) {
+        let Some(current) = camera.get::<Projection>() else {
+            return;
+        };
+        let mut next = current.clone();
+        self.apply(&mut next);
+
+        // The half height of the current view at the anchor.
+        let half_height = match current {
+            Projection::Perspective(perspective) => {
+                Some((perspective.fov as f64 / 2.0).tan() * anchor_depth.abs())
+            }
+            Projection::Orthographic(ortho) => camera
+                .get::<Camera>()
+                .map(|camera| ortho.scale as f64 / ortho_tri_base_to_scale_factor(camera, ortho)),
+            Projection::Custom(_) => None,
+        };
+
+        match (&mut next, current) {
+            (Projection::Perspective(next), Projection::Perspective(current)) => {
+                tween.fov = Some(TweenFov {
+                    from: current.fov as f64,
+                    to: next.fov as f64,
+                    dolly: None,
+                });
+                next.fov = current.fov;
+            }
+            (Projection::Orthographic(next), Projection::Orthographic(current)) => {
+                tween.ortho_scale = Some((current.scale, next.scale));
+                next.scale = current.scale;
+            }
+            (Projection::Perspective(next), _) => {
+                if let Some(half_height) = half_height.filter(|_| anchor_depth != 0.0) {
+                    let fov = 2.0 * (half_height / anchor_depth.abs()).atan();
+                    tween.fov = Some(TweenFov {
+                        from: fov,
+                        to: next.fov as f64,
+                        dolly: None,
+                    });
+                    next.fov = fov as f32;
+                }
+            }
+            (Projection::Orthographic(next), _) => {
+                if let (Some(half_height), Some(camera)) = (half_height, camera.get::<Camera>()) {
+                    let scale = (half_height * ortho_tri_base_to_scale_factor(camera, next)) as f32;
+                    tween.ortho_scale = Some((scale, next.scale));
+                    next.scale = scale;
+                }
+            }
+            (Projection::Custom(_), _) => {}
+        }
+
+        if let Some(mut projection) = camera.get_mut::<Projection>() {
+            *projection = next;
+        }
+    }
+}
+
+/// A saved camera view.
+#[derive(Debug, Clone, Copy, Reflect, Serialize, Deserialize)]
+pub struct ViewBookmark {
+    /// The translation of the camera, as read by the [`TransformAdapter`].
+    pub translation: DVec3,
+    /// The rotation of the camera, as read by the [`TransformAdapter`].
+    pub rotation: DQuat,
+    /// The projection of the camera. This is `None` for custom projections, which are left
+    /// unchanged when the bookmark is recalled.
+    pub projection: Option<BookmarkProjection>,
+    /// The [`EditorCam::last_anchor_depth`] of the camera.
+    pub anchor_depth: f64,
+    /// The [`EditorCam::orbit_constraint`] of the camera.
+    pub orbit_constraint: OrbitConstraint,
+}
+
+impl ViewBookmark {
+    /// Capture the current view of a camera with an [`EditorCam`].
+    pub fn capture(camera: &EntityRef, transform_adapter: &TransformAdapter) -> Option<Self> {
+        let editor_cam = camera.get::<EditorCam>()?;
+        let (translation, rotation) = transform_adapter.read(camera)?;
+        Some(Self {
+            translation,
+            rotation,
+            projection: camera
+                .get::<Projection>()
+                .and_then(BookmarkProjection::capture),
+            anchor_depth: editor_cam.last_anchor_depth(),
+            orbit_constraint: editor_cam.orbit_constraint,
+        })
+    }
+
+    /// Start a smooth transition of this camera to this view, using the label, duration, and
+    /// curve of `tween`. Returns `false` if the current view of the camera could not be read.
+    pub fn transition(
+        &self,
+        tweens: &mut Tweens,
+        camera: &mut EntityMut,
+        transform_adapter: &TransformAdapter,
+        tween: CameraTween,
+    ) -> bool {
+        let Some(initial) = ViewBookmark::capture(&camera.as_readonly(), transform_adapter) else {
+            return false;
+        };
+        let mut tween = CameraTween {
+            rotation: Some(TweenRotation {
+                from: initial.rotation,
+                to: self.rotation,
+                pivot: RotationPivot::Camera,
+            }),
+            translation: Some(self.translation - initial.translation),
+            // The orthographic projection moves the camera along its view axis to keep the anchor
+            // in view while the scale changes, so end at exactly the saved pose.
+            end_translation: Some(self.translation),
+            end_anchor_depth: Some(self.anchor_depth),
+            ..tween
+        };
+        if let Some(projection) = self.projection {
+            projection.animate(camera, initial.anchor_depth, &mut tween);
+        }
+
+        let camera_entity = camera.id();
+        let Some(mut controller) = camera.get_mut::<EditorCam>() else {
+            return false;
+        };
+        controller.orbit_constraint = self.orbit_constraint;
+        controller.last_anchor_depth = -self.anchor_depth.abs();
+        tweens.start(camera_entity, &mut controller, tween);
+        true
+    }
+}
+
+/// A named set of [`ViewBookmark`]s, which can be saved to and loaded from a RON file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BookmarkSet {
+    /// The bookmarks, ordered by name.
+    pub views: BTreeMap<String, ViewBookmark>,
+}
+
+impl BookmarkSet {
+    /// Get the bookmark with this name.
+    pub fn get(&self, name: &str) -> Option<&ViewBookmark> {
+        self.views.get(name)
+    }
+
+    /// Insert a bookmark, returning the previous bookmark with the same name, if any.
+    pub fn insert(&mut self, name: impl Into<String>, view: ViewBookmark) -> Option<ViewBookmark> {
+        self.views.insert(name.into(), view)
+    }
+
+    /// Remove the bookmark with this name.
+    pub fn remove(&mut self, name: &str) -> Option<ViewBookmark> {
+        self.views.remove(name)
+    }
+
+    /// Serialize the bookmarks to a RON string.
+    pub fn to_ron(&self) -> Result<String, BookmarkError> {
+        ron::ser::to_string_pretty(self, Default::default()).map_err(BookmarkError::Serialize)
+    }
+
+    /// Deserialize bookmarks from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, BookmarkError> {
+        ron::from_str(ron).map_err(BookmarkError::Deserialize)
+    }
+
+    /// Save the bookmarks to a RON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BookmarkError> {
+        std::fs::write(path, self.to_ron()?).map_err(BookmarkError::Io)
+    }
+
+    /// Load bookmarks from a RON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookmarkError> {
+        Self::from_ron(&std::fs::read_to_string(path).map_err(BookmarkError::Io)?)
+    }
+}
+
+/// An error encountered while saving or loading a [`BookmarkSet`].
+#[derive(Debug)]
+pub enum BookmarkError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The bookmarks could not be serialized.
+    Serialize(ron::Error),
+    /// The file does not contain valid bookmarks.
+    Deserialize(ron::error::SpannedError),
+}
+
+impl Display for BookmarkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BookmarkError::Io(err) => write!(f, "Unable to access bookmark file: {err}"),
+            BookmarkError::Serialize(err) => write!(f, "Unable to serialize bookmarks: {err}"),
+            BookmarkError::Deserialize(err) => write!(f, "Unable to deserialize bookmarks: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BookmarkError {}
+
+/// Stores settings and saved views for the bookmarks plugin.
+#[derive(Resource, Reflect)]
+pub struct Bookmarks {
+    /// The saved views.
+    #[reflect(ignore)]
+    pub views: BookmarkSet,
+    /// The duration of the transition animation when recalling a bookmark.
+    pub animation_duration: Duration,
+    /// The cubic curve used to animate the camera when recalling a bookmark.
+    #[reflect(ignore)]
+    pub animation_curve: CubicSegment<Vec2>,
+}
+
+impl Default for Bookmarks {
+    fn default() -> Self {
+        Self {
+            views: Default::default(),
+            animation_duration: Duration::from_millis(600),
+            animation_curve: CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
+        }
+    }
+}
+
+impl Bookmarks {
+    /// The [`CameraTween::label`] of bookmark transitions.
+    pub const TWEEN_LABEL: &'static str = "bookmark";
+
+    /// Start a smooth transition of this camera to the given view. This does not need to be a
+    /// saved bookmark. Returns `false` if the current view of the camera could not be read.
+    pub fn transition_to(
+        &self,
+        tweens: &mut Tweens,
+        camera: &mut EntityMut,
+        view: ViewBookmark,
+        transform_adapter: &TransformAdapter,
+    ) -> bool {
+        let tween = CameraTween::new(Self::TWEEN_LABEL)
+            .with_animation(self.animation_duration, self.animation_curve);
+        view.transition(tweens, camera, transform_adapter, tween)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::RenderTargetInfo;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::UVec2;
+    use bevy_transform::prelude::*;
+
+    use super::*;
+    use crate::{controller::projections::update_orthographic, extensions::tween::TweenComplete};
+
+    fn camera_world() -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Tweens>();
+        world.init_resource::<TransformAdapter>();
+        world.init_resource::<Messages<RequestRedraw>>();
+        world.init_resource::<Messages<TweenComplete>>();
+
+        let mut projection = Projection::Orthographic(OrthographicProjection::default_3d());
+        projection.update(1280.0, 720.0);
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(1280, 720),
+            scale_factor: 1.0,
+        });
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+        let editor_cam = EditorCam::default().with_initial_anchor_depth(10.0);
+        let entity = world
+            .spawn((
+                editor_cam,
+                camera,
+                projection,
+                Transform::default(),
+                GlobalTransform::default(),
+            ))
+            .id();
+        (world, entity)
+    }
+
+    fn capture(world: &mut World, entity: Entity) -> ViewBookmark {
+        let adapter = world.resource::<TransformAdapter>().clone();
+        ViewBookmark::capture(&world.entity(entity), &adapter).unwrap()
+    }
+
+    #[test]
+    fn recall_orthographic() {
+        let (mut world, entity) = camera_world();
+        world.run_system_once(update_orthographic).unwrap();
+        let saved = capture(&mut world, entity);
+
+        // Move, rotate, and zoom the camera away from the saved view.
+        let mut transform = world.get_mut::<Transform>(entity).unwrap();
+        transform.translation += Vec3::new(40.0, -25.0, 10.0);
+        transform.rotate_y(0.8);
+        transform.rotate_local_x(-0.4);
+        if let Projection::Orthographic(ortho) = &mut *world.get_mut::<Projection>(entity).unwrap()
+        {
+            ortho.scale = 3.0;
+        }
+        world.run_system_once(update_orthographic).unwrap();
+
+        let tween = CameraTween::new(Bookmarks::TWEEN_LABEL).with_animation(
+            Duration::from_millis(50),
+            CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
+        );
+        world.resource_scope(|world, mut tweens: Mut<Tweens>| {
+            let adapter = world.resource::<TransformAdapter>().clone();
+            let mut camera: EntityMut = world.entity_mut(entity).into();
+            assert!(saved.transition(&mut tweens, &mut camera, &adapter, tween));
+        });
+        while world.resource::<Tweens>().is_animating(entity) {
+            std::thread::sleep(Duration::from_millis(5));
+            world.run_system_once(Tweens::update).unwrap();
+            world.run_system_once(update_orthographic).unwrap();
+        }
+        world.run_system_once(update_orthographic).unwrap();
+
+        let recalled = capture(&mut world, entity);
+        let offset = (recalled.translation - saved.translation).length();
+        assert!(offset < 1e-6, "ended {offset} from the saved translation");
+        assert!(recalled.rotation.angle_between(saved.rotation) < 1e-4);
+        assert!((recalled.anchor_depth - saved.anchor_depth).abs() < 1e-2);
+        let (
+            Some(BookmarkProjection::Orthographic { scale, .. }),
+            Some(BookmarkProjection::Orthographic {
+                scale: saved_scale, ..
+            }),
+        ) = (recalled.projection, saved.projection)
+        else {
+            panic!("the projection is not orthographic");
+        };
+        assert_eq!(scale, saved_scale);
+    }
+}
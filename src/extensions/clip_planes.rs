@@ -0,0 +1,295 @@
+//! A `bevy_editor_cam` extension that fits the near and far clipping planes of a camera tightly
+//! around the scene, instead of placing them relative to the anchor.
+//!
+//! The controller places the near plane of a perspective camera at a fraction of the anchor depth,
+//! and the clipping planes of an orthographic camera around the anchor. This keeps the anchor
+//! visible, but depth precision is only good near the anchor. With an [`AutoClipPlanes`]
+//! component, the planes are fit to the depth range of the scene bounds every frame, which
+//! improves depth precision, and depth based effects like SSAO, across the whole scene.
+//!
+//! The bounds of visible entities are computed from their [`Aabb`] and [`GlobalTransform`]. An
+//! entity is included if it is not hidden, and it is inside the sides of the view frustum of the
+//! camera. The near and far planes are ignored, so geometry that is clipped by the current planes
+//! is still included, and the planes grow to fit it again.
+
+use bevy_app::prelude::*;
+use bevy_camera::{
+    prelude::*,
+    primitives::{Aabb, Frustum},
+    CameraUpdateSystems,
+};
+use bevy_ecs::{prelude::*, system::SystemId};
+use bevy_log::prelude::*;
+use bevy_math::{DMat4, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_transform::{prelude::*, TransformSystems};
+
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct ClipPlanesPlugin;
+
+impl Plugin for ClipPlanesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PostUpdate,
+            AutoClipPlanes::update
+                .after(TransformSystems::Propagate)
+                .before(CameraUpdateSystems),
+        );
+    }
+}
+
+/// Entities with this component are not included in the scene bounds used by
+/// [`ClipBoundsSource::VisibleEntities`]. Useful for very large or infinite geometry, like ground
+/// planes and grids.
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+pub struct IgnoreClipBounds;
+
+/// Where the scene bounds used to fit the clipping planes come from.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum ClipBoundsSource {
+    /// The bounds of all entities with an [`Aabb`] that are not hidden, and are inside the sides of
+    /// the view frustum of the camera, except entities with [`IgnoreClipBounds`].
+    #[default]
+    VisibleEntities,
+    /// A registered system that is given the camera entity, and returns the world space bounds of
+    /// the scene as the minimum and maximum corners of a box.
+    System(SystemId<In<Entity>, Option<(DVec3, DVec3)>>),
+}
+
+/// Optional. Fits the near and far clipping planes of an [`EditorCam`] to the scene bounds. See the
+/// [module](self) docs.
+#[derive(Debug, Clone, Component)]
+pub struct AutoClipPlanes {
+    /// Should the clipping planes be fit to the scene bounds?
+    pub enabled: bool,
+    /// Where the scene bounds come from.
+    pub source: ClipBoundsSource,
+    /// Moves the planes away from the bounds, as a fraction of their depth, to prevent geometry on
+    /// the bounds from being clipped. The near plane is placed at `1.0 - margin` times the nearest
+    /// depth, and the far plane at `1.0 + margin` times the farthest depth.
+    pub margin: f64,
+}
+
+impl Default for AutoClipPlanes {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            source: ClipBoundsSource::VisibleEntities,
+            margin: 0.05,
+        }
+    }
+}
+
+impl AutoClipPlanes {
+    /// Fit the clipping planes to bounds provided by a registered system.
+    pub fn from_system(system: SystemId<In<Entity>, Option<(DVec3, DVec3)>>) -> Self {
+        Self {
+            source: ClipBoundsSource::System(system),
+            ..Default::default()
+        }
+    }
+
+    fn update(
+        world: &mut World,
+        cameras: &mut QueryState<
+            (Entity, &AutoClipPlanes, &GlobalTransform, &Projection),
+            With<EditorCam>,
+        >,
+        bounds: &mut QueryState<
+            (&Aabb, &GlobalTransform, &InheritedVisibility),
+            Without<IgnoreClipBounds>,
+        >,
+    ) {
+        let requests = cameras
+            .iter(world)
+            .filter(|(_, settings, ..)| settings.enabled)
+            .map(|(camera, settings, transform, projection)| {
+                let world_to_view = EditorCam::view_to_world(transform).inverse();
+                let depth_scale = EditorCam::depth_scale(transform);
+                let frustum = view_frustum(projection, transform);
+                let source = settings.source;
+                let margin = settings.margin;
+                (camera, source, margin, world_to_view, depth_scale, frustum)
+            })
+            .collect::<Vec<_>>();
+
+        for (camera, source, margin, world_to_view, depth_scale, frustum) in requests {
+            let range = match source {
+                ClipBoundsSource::VisibleEntities => depth_range(
+                    world_to_view,
+                    bounds
+                        .iter(world)
+                        .filter(|(aabb, transform, visibility)| {
+                            // The view visibility of the entity can't be used, because it is culled
+                            // by the clipping planes that are being fit.
+                            visibility.get() && in_view(&frustum, aabb, transform)
+                        })
+                        .flat_map(|(aabb, transform, _)| {
+                            let center = aabb.center.as_dvec3();
+                            let half = aabb.half_extents.as_dvec3();
+                            let to_world = transform.to_matrix().as_dmat4();
+                            corners(center - half, center + half)
+                                .map(move |corner| to_world.transform_point3(corner))
+                        }),
+                ),
+                ClipBoundsSource::System(system) => match world.run_system_with(system, camera) {
+                    Ok(bounds) => bounds.and_then(|(min, max)| {
+                        depth_range(world_to_view, corners(min.min(max), min.max(max)))
+                    }),
+                    Err(err) => {
+                        error_once!("Unable to run the clip bounds system: {err}");
+                        None
+                    }
+                },
+            };
+            let Some((nearest, farthest)) = range else {
+                continue;
+            };
+            let Ok(mut camera_mut) = world.get_entity_mut(camera) else {
+                continue;
+            };
+            let Some(editor_cam) = camera_mut.get::<EditorCam>() else {
+                continue;
+            };
+            // Never clip the anchor, the user is looking at it.
+            let anchor_dist = editor_cam.last_anchor_depth().abs();
+            let near_clip_limits = editor_cam.perspective.near_clip_limits.clone();
+            let Some(mut projection) = camera_mut.get_mut::<Projection>() else {
+                continue;
+            };
+
+            let far = (farthest * (1.0 + margin)).max(anchor_dist * (1.0 + margin));
+            let mut changed = false;
+            // Depths are in world units, the planes are in the scaled view space of the camera.
+            let mut set_if_neq = |value: &mut f32, new: f64| {
+                let new = (new / depth_scale) as f32;
+                if *value != new && new.is_finite() {
+                    *value = new;
+                    changed = true;
+                }
+            };
+            // Avoid triggering change detection, which recomputes the camera frustum, every frame.
+            match projection.bypass_change_detection() {
+                Projection::Perspective(perspective) => {
+                    if nearest <= 0.0 {
+                        // The camera is inside the bounds, so the nearest geometry is unknown.
+                        // Keep the near plane placed by the controller.
+                        set_if_neq(&mut perspective.far, far);
+                    } else {
+                        let near = (nearest * (1.0 - margin))
+                            .min(anchor_dist * (1.0 - margin))
+                            .max(0.0) as f32;
+                        let near = near.clamp(near_clip_limits.start, near_clip_limits.end);
+                        set_if_neq(&mut perspective.near, near as f64);
+                        set_if_neq(&mut perspective.far, far);
+                    }
+                }
+                Projection::Orthographic(ortho) => {
+                    let near = (nearest * (1.0 - margin))
+                        .min(anchor_dist * (1.0 - margin))
+                        .max(0.0);
+                    set_if_neq(&mut ortho.near, near);
+                    set_if_neq(&mut ortho.far, far);
+                }
+                Projection::Custom(_) => {
+                    error_once!("Automatic clip planes are not supported for custom projections.");
+                }
+            }
+            if changed {
+                projection.set_changed();
+            }
+        }
+    }
+}
+
+/// The view frustum of a camera with this projection and transform.
+fn view_frustum(projection: &Projection, transform: &GlobalTransform) -> Frustum {
+    let clip_from_world = projection.get_clip_from_view() * transform.to_matrix().inverse();
+    Frustum::from_clip_from_world(&clip_from_world)
+}
+
+/// Is the box inside the sides of the `frustum`? The near and far planes are ignored.
+fn in_view(frustum: &Frustum, aabb: &Aabb, transform: &GlobalTransform) -> bool {
+    frustum.intersects_obb(aabb, &transform.affine(), false, false)
+}
+
+/// The corners of a box.
+fn corners(min: DVec3, max: DVec3) -> impl Iterator<Item = DVec3> {
+    (0..8).map(move |i| {
+        DVec3::new(
+            if i & 1 == 0 { min.x } else { max.x },
+            if i & 2 == 0 { min.y } else { max.y },
+            if i & 4 == 0 { min.z } else { max.z },
+        )
+    })
+}
+
+/// The nearest and farthest depth of these world space points in front of the camera. Returns
+/// `None` if all of the points are behind the camera.
+fn depth_range(world_to_view: DMat4, points: impl Iterator<Item = DVec3>) -> Option<(f64, f64)> {
+    let (nearest, farthest) = points
+        .map(|point| -world_to_view.transform_point3(point).z)
+        .filter(|depth| depth.is_finite())
+        .fold(None, |acc: Option<(f64, f64)>, depth| match acc {
+            Some((nearest, farthest)) => Some((nearest.min(depth), farthest.max(depth))),
+            None => Some((depth, depth)),
+        })?;
+    (farthest > 0.0).then_some((nearest, farthest))
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Vec3;
+
+    use super::*;
+
+    /// A camera at the origin, looking down -Z.
+    const WORLD_TO_VIEW: DMat4 = DMat4::IDENTITY;
+
+    #[test]
+    fn points_in_front() {
+        let points = [DVec3::new(1.0, 2.0, -3.0), DVec3::new(-4.0, 0.0, -8.0)];
+        let range = depth_range(WORLD_TO_VIEW, points.into_iter());
+        assert_eq!(range, Some((3.0, 8.0)));
+    }
+
+    #[test]
+    fn points_behind() {
+        let points = [DVec3::new(0.0, 0.0, 1.0), DVec3::new(5.0, 0.0, 8.0)];
+        let range = depth_range(WORLD_TO_VIEW, points.into_iter());
+        assert_eq!(range, None);
+    }
+
+    #[test]
+    fn ignores_near_and_far_planes() {
+        let mut projection = Projection::Perspective(PerspectiveProjection {
+            near: 1.0,
+            far: 10.0,
+            ..Default::default()
+        });
+        projection.update(1280.0, 720.0);
+        let frustum = view_frustum(&projection, &GlobalTransform::IDENTITY);
+        let aabb = Aabb::from_min_max(Vec3::splat(-0.1), Vec3::splat(0.1));
+        let at = |x: f32, z: f32| GlobalTransform::from_translation(Vec3::new(x, 0.0, z));
+
+        assert!(in_view(&frustum, &aabb, &at(0.0, -5.0)));
+        // Clipped by the planes, but still in view.
+        assert!(in_view(&frustum, &aabb, &at(0.0, -0.5)));
+        assert!(in_view(&frustum, &aabb, &at(0.0, -100.0)));
+        // Outside the sides of the view, and behind the camera.
+        assert!(!in_view(&frustum, &aabb, &at(100.0, -5.0)));
+        assert!(!in_view(&frustum, &aabb, &at(0.0, 5.0)));
+    }
+
+    #[test]
+    fn points_around_camera() {
+        // The nearest depth is behind the camera, so the camera is inside the bounds.
+        let range = depth_range(
+            WORLD_TO_VIEW,
+            corners(DVec3::splat(-2.0), DVec3::new(2.0, 2.0, 10.0)),
+        );
+        assert_eq!(range, Some((-10.0, 2.0)));
+    }
+}
@@ -0,0 +1,234 @@
+//! A `bevy_editor_cam` extension that prevents the camera from getting closer than a minimum
+//! distance to scene geometry while it is moving. This stops the near clipping plane from ending up
+//! inside walls when orbiting, panning, or zooming in tight spaces.
+//!
+//! The controller does not know anything about your scene geometry, so it uses a [`RayCaster`] to
+//! find it. To use it, add a [`CollisionAvoidance`] component to a camera, and add the
+//! [`CollisionPlugin`] to your app.
+//!
+//! With the `mesh_picking` feature, the ray casting of the mesh picking backend can be used
+//! directly, with `RayCaster::MeshPicking`. Otherwise, provide a registered one-shot system that
+//! takes a [`CameraRay`] and returns the distance to the nearest hit, if any:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_editor_cam::prelude::{ray_cast::*, *};
+//! # use bevy_editor_cam::extensions::collision::*;
+//! fn ray_cast(In(ray): In<CameraRay>, mut ray_cast: MeshRayCast) -> Option<f64> {
+//!     let settings = MeshRayCastSettings::default().always_early_exit();
+//!     let ray3d = Ray3d::new(ray.origin.as_vec3(), Dir3::new(ray.direction.as_vec3()).ok()?);
+//!     ray_cast
+//!         .cast_ray(ray3d, &settings)
+//!         .first()
+//!         .map(|(_, hit)| hit.distance as f64)
+//! }
+//!
+//! fn setup(mut commands: Commands) {
+//!     let ray_cast = commands.register_system(ray_cast);
+//!     commands.spawn((
+//!         Camera3d::default(),
+//!         EditorCam::default(),
+//!         CollisionAvoidance::new(ray_cast, 0.2),
+//!     ));
+//! }
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_camera::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{DQuat, DVec3};
+
+use crate::prelude::{
+    momentum::Velocity,
+    motion::CurrentMotion,
+    ray_cast::{CameraRay, RayCaster},
+    EditorCam, TransformAdapter,
+};
+
+/// See the [module](self) docs.
+pub struct CollisionPlugin;
+
+impl Plugin for CollisionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (
+                CollisionAvoidance::record_positions
+                    .before(crate::controller::component::EditorCam::update_camera_positions),
+                CollisionAvoidance::resolve
+                    .after(crate::controller::component::EditorCam::update_camera_positions)
+                    .before(crate::controller::projections::update_orthographic),
+            )
+                .in_set(crate::SyncCameraPosition),
+        );
+    }
+}
+
+/// Optional. Keeps an [`EditorCam`] at least [`CollisionAvoidance::min_distance`] away from any
+/// surface found by its [`RayCaster`], during all camera motions, including momentum.
+///
+/// Rays are cast in the same space as the translation read by the [`TransformAdapter`]. With the
+/// default adapter, this is world space.
+///
+/// Collision avoidance only applies to cameras with a perspective [`Projection`]. Orthographic
+/// cameras are moved along their view axis to manage clipping planes, so their position is not
+/// meaningful for collisions.
+#[derive(Debug, Clone, Component)]
+pub struct CollisionAvoidance {
+    /// Should collision avoidance be applied to this camera?
+    pub enabled: bool,
+    /// The minimum distance the camera should keep from any surface. This should be larger than
+    /// the near clipping plane of the camera.
+    pub min_distance: f64,
+    /// Used to cast rays into the scene.
+    pub ray_cast: RayCaster,
+    /// The camera position at the start of this frame, before the controller moved it.
+    last_position: Option<DVec3>,
+}
+
+impl CollisionAvoidance {
+    /// Create a new [`CollisionAvoidance`] component with the provided ray caster, or ray casting
+    /// system.
+    pub fn new(ray_cast: impl Into<RayCaster>, min_distance: f64) -> Self {
+        Self {
+            enabled: true,
+            min_distance: min_distance.abs(),
+            ray_cast: ray_cast.into(),
+            last_position: None,
+        }
+    }
+
+    /// Create a new [`CollisionAvoidance`] component that uses the ray casting of the mesh picking
+    /// backend.
+    #[cfg(feature = "mesh_picking")]
+    pub fn mesh_picking(min_distance: f64) -> Self {
+        Self::new(RayCaster::MeshPicking, min_distance)
+    }
+
+    /// Directions in view space used to probe for nearby geometry.
+    const PROBE_DIRECTIONS: [DVec3; 6] = [
+        DVec3::NEG_Z,
+        DVec3::Z,
+        DVec3::X,
+        DVec3::NEG_X,
+        DVec3::Y,
+        DVec3::NEG_Y,
+    ];
+
+    /// Records the position of the camera before the controller moves it, so the motion can be
+    /// swept for collisions afterward.
+    pub fn record_positions(
+        mut cameras: Query<EntityMut, With<CollisionAvoidance>>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        for mut camera_mut in &mut cameras {
+            let position = transform_adapter
+                .read(&camera_mut.as_readonly())
+                .map(|(translation, _)| translation);
+            if let Some(mut collision) = camera_mut.get_mut::<CollisionAvoidance>() {
+                collision.last_position = position;
+            }
+        }
+    }
+
+    /// Moves cameras away from geometry after the controller has updated their positions.
+    pub fn resolve(world: &mut World) {
+        let mut cameras = world.query::<(Entity, &EditorCam, &CollisionAvoidance, &Projection)>();
+        let candidates = cameras
+            .iter(world)
+            .filter(|(_, editor_cam, collision, projection)| {
+                collision.enabled
+                    && editor_cam.current_motion.is_moving()
+                    && matches!(projection, Projection::Perspective(_))
+            })
+            .map(|(entity, _, collision, _)| (entity, collision.clone()))
+            .collect::<Vec<_>>();
+
+        world.resource_scope(|world, transform_adapter: Mut<TransformAdapter>| {
+            for (camera, collision) in candidates {
+                let Some((translation, rotation)) = transform_adapter.read(&world.entity(camera))
+                else {
+                    continue;
+                };
+                let resolved = collision.resolve_position(world, camera, translation, rotation);
+                let correction = resolved - translation;
+                if correction == DVec3::ZERO || !correction.is_finite() {
+                    continue;
+                }
+
+                let correction_view_space = rotation.inverse() * correction;
+                let mut entity = world.entity_mut(camera);
+                if let Some(mut editor_cam) = entity.get_mut::<EditorCam>() {
+                    shift_anchor(&mut editor_cam, correction_view_space);
+                }
+                transform_adapter.apply_delta(
+                    &mut (&mut entity).into(),
+                    correction_view_space,
+                    DQuat::IDENTITY,
+                );
+            }
+        });
+    }
+
+    /// Sweep the camera motion since the last recorded position, then push the camera away from
+    /// any nearby surfaces. Returns the corrected camera position.
+    fn resolve_position(
+        &self,
+        world: &mut World,
+        camera: Entity,
+        translation: DVec3,
+        rotation: DQuat,
+    ) -> DVec3 {
+        let mut ray_cast = |origin: DVec3, direction: DVec3, max_distance: f64| {
+            let ray = CameraRay {
+                camera,
+                origin,
+                direction,
+                max_distance,
+            };
+            self.ray_cast.cast(world, ray)
+        };
+
+        let mut position = translation;
+
+        // Prevent fast motions from tunneling through thin geometry.
+        if let Some(last_position) = self.last_position {
+            let motion = position - last_position;
+            let distance = motion.length();
+            if distance > f64::EPSILON && motion.is_finite() {
+                let direction = motion / distance;
+                if let Some(hit) = ray_cast(last_position, direction, distance + self.min_distance)
+                {
+                    let allowed = (hit - self.min_distance).max(0.0);
+                    if allowed < distance {
+                        position = last_position + direction * allowed;
+                    }
+                }
+            }
+        }
+
+        // Push the camera out of any geometry that is closer than the minimum distance.
+        for direction in Self::PROBE_DIRECTIONS.map(|d| rotation * d) {
+            if let Some(hit) = ray_cast(position, direction, self.min_distance) {
+                position -= direction * (self.min_distance - hit);
+            }
+        }
+
+        position
+    }
+}
+
+/// Keep the anchor fixed in world space after the camera has been moved by `offset` in view space.
+fn shift_anchor(editor_cam: &mut EditorCam, offset: DVec3) {
+    match &mut editor_cam.current_motion {
+        CurrentMotion::Stationary => (),
+        CurrentMotion::Momentum { velocity, .. } => match velocity {
+            Velocity::None => (),
+            Velocity::Orbit { anchor, .. }
+            | Velocity::Pan { anchor, .. }
+            | Velocity::Zoom { anchor, .. } => *anchor -= offset,
+        },
+        CurrentMotion::UserControlled { anchor, .. } => *anchor -= offset,
+    }
+    editor_cam.last_anchor_depth = editor_cam.last_anchor_depth() - offset.z;
+}
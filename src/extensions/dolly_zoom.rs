@@ -6,28 +6,29 @@
 use std::time::Duration;
 
 use bevy_app::prelude::*;
-use bevy_camera::{prelude::*, ScalingMode};
+use bevy_camera::prelude::*;
 use bevy_ecs::prelude::*;
 use bevy_log::error_once;
 use bevy_math::{prelude::*, DQuat, DVec3};
-use bevy_platform::{collections::HashMap, time::Instant};
 use bevy_reflect::prelude::*;
 use bevy_window::RequestRedraw;
 
-use crate::prelude::{motion::CurrentMotion, EditorCam, EnabledMotion, TransformAdapter};
+use super::tween::{CameraTween, TweenFov, TweenPlugin, Tweens};
+use crate::{
+    controller::zoom::ortho_tri_base_to_scale_factor,
+    prelude::{EditorCam, ProjectionAdapters, TransformAdapter},
+};
 
 /// See the [module](self) docs.
 pub struct DollyZoomPlugin;
 
 impl Plugin for DollyZoomPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
         app.init_resource::<DollyZoom>()
             .add_message::<DollyZoomTrigger>()
-            .add_systems(
-                PreUpdate,
-                DollyZoom::update
-                    .before(crate::controller::component::EditorCam::update_camera_positions),
-            )
             .add_systems(Last, DollyZoomTrigger::receive); // This mutates camera components, so we want to be sure it runs *after* rendering has happened. We place it in Last to ensure that we wake the next frame if needed. If we run this in PostUpdate, this can result in rendering artifacts because this will mutate projections right before rendering.
     }
 }
@@ -47,12 +48,14 @@ pub struct DollyZoomTrigger {
 impl DollyZoomTrigger {
     fn receive(
         mut events: MessageReader<Self>,
-        mut state: ResMut<DollyZoom>,
+        state: Res<DollyZoom>,
+        mut tweens: ResMut<Tweens>,
         mut camera_set: ParamSet<(
             Query<(&Camera, Mut<Projection>, &mut EditorCam)>,
             Query<EntityMut, With<EditorCam>>,
         )>,
         transform_adapter: Res<TransformAdapter>,
+        projection_adapters: Res<ProjectionAdapters>,
         mut redraw: MessageWriter<RequestRedraw>,
     ) {
         for event in events.read() {
@@ -62,6 +65,20 @@ impl DollyZoomTrigger {
             };
             let mut delta_translation = DVec3::ZERO;
             redraw.write(RequestRedraw);
+            let fov_end = match &event.target_projection {
+                Projection::Perspective(perspective) => perspective.fov as f64,
+                Projection::Orthographic(_) => ZERO_FOV,
+                Projection::Custom(custom) => {
+                    let Some(fov) = projection_adapters.get(custom).and_then(|adapter| {
+                        adapter.equivalent_fov(camera, controller.last_anchor_depth())
+                    }) else {
+                        error_once!("Custom projection has no registered `ProjectionAdapter`.");
+                        continue;
+                    };
+                    fov
+                }
+            };
+
             let (fov_start, triangle_base) = match &*proj {
                 Projection::Perspective(perspective) => {
                     if let Projection::Perspective(PerspectiveProjection {
@@ -95,9 +112,17 @@ impl DollyZoomTrigger {
 
                     (ZERO_FOV as f32, base)
                 }
-                Projection::Custom(_) => {
-                    error_once!("Custom projections are not supported.");
-                    return;
+                Projection::Custom(custom) => {
+                    let Some(fov) = projection_adapters.get(custom).and_then(|adapter| {
+                        adapter.equivalent_fov(camera, controller.last_anchor_depth())
+                    }) else {
+                        error_once!("Custom projection has no registered `ProjectionAdapter`.");
+                        continue;
+                    };
+                    (
+                        fov as f32,
+                        (fov / 2.0).tan() * controller.last_anchor_depth.abs(),
+                    )
                 }
             };
 
@@ -107,50 +132,29 @@ impl DollyZoomTrigger {
             };
             *proj = Projection::Perspective(perspective_start.clone());
 
-            state
-                .map
-                .entry(event.camera)
-                .and_modify(|e| {
-                    e.perspective_start = perspective_start.clone();
-                    e.proj_end = event.target_projection.clone();
-                    e.triangle_base = triangle_base;
-                    e.start = Instant::now();
-                    e.complete = false;
-                })
-                .or_insert(ZoomEntry {
-                    perspective_start,
-                    proj_end: event.target_projection.clone(),
-                    triangle_base,
-                    start: Instant::now(),
-                    initial_enabled: controller.enabled_motion.clone(),
-                    complete: false,
-                });
-
-            controller.end_move();
-            controller.current_motion = CurrentMotion::Stationary;
-            controller.enabled_motion = EnabledMotion {
-                pan: false,
-                orbit: false,
-                zoom: false,
-            };
+            let tween = CameraTween {
+                fov: Some(TweenFov {
+                    from: fov_start as f64,
+                    to: fov_end,
+                    dolly: Some(triangle_base),
+                }),
+                end_projection: Some(event.target_projection.clone()),
+                disable_motion: true,
+                ..CameraTween::new(DollyZoom::TWEEN_LABEL)
+            }
+            .with_animation(state.animation_duration, state.animation_curve);
+            tweens.start(event.camera, &mut controller, tween);
 
             let mut camera_muts = camera_set.p1();
-            let mut camera_mut = camera_muts.get_mut(event.camera).unwrap();
+            let Ok(mut camera_mut) = camera_muts.get_mut(event.camera) else {
+                continue;
+            };
             transform_adapter.apply_delta(&mut camera_mut, delta_translation, DQuat::IDENTITY);
         }
     }
 }
 
-struct ZoomEntry {
-    perspective_start: PerspectiveProjection,
-    proj_end: Projection,
-    triangle_base: f64,
-    start: Instant,
-    initial_enabled: EnabledMotion,
-    complete: bool,
-}
-
-/// Stores settings and state for the dolly zoom plugin.
+/// Stores settings for the dolly zoom plugin.
 #[derive(Resource, Reflect)]
 pub struct DollyZoom {
     /// The duration of the dolly zoom transition animation.
@@ -158,8 +162,6 @@ pub struct DollyZoom {
     /// The cubic curve used to animate the camera during a dolly zoom.
     #[reflect(ignore)]
     pub animation_curve: CubicSegment<Vec2>,
-    #[reflect(ignore)]
-    map: HashMap<Entity, ZoomEntry>,
 }
 
 impl Default for DollyZoom {
@@ -167,131 +169,11 @@ impl Default for DollyZoom {
         Self {
             animation_duration: Duration::from_millis(300),
             animation_curve: CubicSegment::new_bezier_easing((0.65, 0.0), (0.35, 1.0)),
-            map: Default::default(),
         }
     }
 }
 
 impl DollyZoom {
-    fn update(
-        mut state: ResMut<Self>,
-        mut camera_set: ParamSet<(
-            Query<(&Camera, Mut<Projection>, &mut EditorCam)>,
-            Query<EntityMut, With<EditorCam>>,
-        )>,
-        transform_adapter: Res<TransformAdapter>,
-        mut redraw: MessageWriter<RequestRedraw>,
-    ) {
-        let animation_duration = state.animation_duration;
-        let animation_curve = state.animation_curve;
-        for (
-            camera_entity,
-            ZoomEntry {
-                perspective_start,
-                proj_end,
-                triangle_base,
-                start,
-                initial_enabled,
-                complete,
-            },
-        ) in state.map.iter_mut()
-        {
-            let mut cameras = camera_set.p0();
-            let Ok((camera, mut projection, mut controller)) = cameras.get_mut(*camera_entity)
-            else {
-                *complete = true;
-                continue;
-            };
-            let Projection::Perspective(last_perspective) = projection.clone() else {
-                *projection = proj_end.clone();
-                controller.enabled_motion = initial_enabled.clone();
-                *complete = true;
-                continue;
-            };
-
-            let mut delta_translation = DVec3::ZERO;
-
-            let last_fov = last_perspective.fov as f64;
-            let fov_start = perspective_start.fov as f64;
-
-            let fov_end = match &*proj_end {
-                Projection::Perspective(perspective) => perspective.fov as f64,
-                Projection::Orthographic(_) => ZERO_FOV,
-                Projection::Custom(_) => {
-                    error_once!("Custom projections are not supported.");
-                    return;
-                }
-            };
-            let progress = start.elapsed().as_secs_f32() / animation_duration.as_secs_f32();
-            let progress = animation_curve.ease(progress);
-            let next_fov = (1.0 - progress as f64) * fov_start + progress as f64 * fov_end;
-
-            let last_dist = *triangle_base / (last_fov / 2.0).tan();
-            let next_dist = *triangle_base / (next_fov / 2.0).tan();
-            let forward_dist = last_dist - next_dist;
-            let cam_forward = DVec3::NEG_Z;
-            let next_translation = cam_forward * forward_dist;
-
-            delta_translation += next_translation;
-            controller.last_anchor_depth += forward_dist;
-
-            if progress < 1.0 {
-                *projection = Projection::Perspective(PerspectiveProjection {
-                    fov: next_fov as f32,
-                    ..last_perspective
-                })
-            } else {
-                *projection = proj_end.clone();
-                if let Projection::Orthographic(ortho) = &mut *projection {
-                    let multiplier = ortho_tri_base_to_scale_factor(camera, ortho);
-
-                    ortho.scale = (*triangle_base * multiplier) as f32;
-                }
-                controller.enabled_motion = initial_enabled.clone();
-                *complete = true;
-            }
-            redraw.write(RequestRedraw);
-
-            let mut camera_muts = camera_set.p1();
-            let mut camera_mut = camera_muts.get_mut(*camera_entity).unwrap();
-            transform_adapter.apply_delta(&mut camera_mut, delta_translation, DQuat::IDENTITY);
-        }
-        state.map.retain(|_, v| !v.complete);
-    }
-}
-
-fn ortho_tri_base_to_scale_factor(camera: &Camera, ortho: &OrthographicProjection) -> f64 {
-    if let Some(size) = camera.logical_viewport_size() {
-        let (width, height) = (size.x as f64, size.y as f64);
-        2.0 / match ortho.scaling_mode {
-            ScalingMode::WindowSize => height,
-            ScalingMode::AutoMin {
-                min_width,
-                min_height,
-            } => {
-                if width * min_height as f64 > min_width as f64 * height {
-                    min_height as f64
-                } else {
-                    height * min_width as f64 / width
-                }
-            }
-            ScalingMode::AutoMax {
-                max_width,
-                max_height,
-            } => {
-                if (width * max_height as f64) < max_width as f64 * height {
-                    max_height as f64
-                } else {
-                    height * max_width as f64 / width
-                }
-            }
-            ScalingMode::FixedVertical { viewport_height } => viewport_height as f64,
-            ScalingMode::FixedHorizontal { viewport_width } => {
-                height * viewport_width as f64 / width
-            }
-            ScalingMode::Fixed { height, .. } => height as f64,
-        }
-    } else {
-        0.00278
-    }
+    /// The [`CameraTween::label`] of dolly zoom animations.
+    pub const TWEEN_LABEL: &'static str = "dolly_zoom";
 }
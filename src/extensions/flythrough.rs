@@ -0,0 +1,598 @@
+//! A `bevy_editor_cam` extension that records a sequence of camera keyframes, and plays them back
+//! along a smooth path. This is useful for making design review videos, without needing to script
+//! camera paths outside of the camera controller.
+//!
+//! Keyframes are stored in a [`FlythroughPath`], which can be saved to and loaded from a RON file.
+//! To play a path back, add a [`FlythroughPlayer`] to a camera with an [`EditorCam`], and call
+//! [`FlythroughPlayer::play`]. User control of the camera is disabled while the path is playing.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy_editor_cam::prelude::*;
+//! # use bevy_editor_cam::extensions::flythrough::*;
+//! fn record(
+//!     mut path: Local<FlythroughPath>,
+//!     cameras: Query<EntityRef, With<EditorCam>>,
+//!     transform_adapter: Res<TransformAdapter>,
+//!     keys: Res<ButtonInput<KeyCode>>,
+//! ) {
+//!     let Ok(camera) = cameras.single() else {
+//!         return;
+//!     };
+//!     if keys.just_pressed(KeyCode::KeyK) {
+//!         // Add a keyframe two seconds after the last one.
+//!         let time = path.duration() + 2.0;
+//!         path.capture(&camera, &transform_adapter, time);
+//!     }
+//!     if keys.just_pressed(KeyCode::KeyS) {
+//!         if let Err(err) = path.save("flythrough.ron") {
+//!             error!("{err}");
+//!         }
+//!     }
+//! }
+//! ```
+
+use std::{fmt::Display, path::Path};
+
+use bevy_app::prelude::*;
+use bevy_camera::prelude::*;
+use bevy_ecs::{lifecycle::HookContext, prelude::*, world::DeferredWorld};
+use bevy_math::{DAffine3, DQuat, DVec3};
+use bevy_time::prelude::*;
+use bevy_window::RequestRedraw;
+use serde::{Deserialize, Serialize};
+
+use super::bookmarks::BookmarkProjection;
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct FlythroughPlugin;
+
+impl Plugin for FlythroughPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            FlythroughPlayer::update
+                .before(crate::controller::component::EditorCam::update_camera_positions)
+                .in_set(crate::SyncCameraPosition),
+        );
+    }
+}
+
+/// A camera pose at a point in time along a [`FlythroughPath`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Keyframe {
+    /// The time of this keyframe, in seconds from the start of the path.
+    pub time: f64,
+    /// The translation of the camera, as read by the [`TransformAdapter`].
+    pub translation: DVec3,
+    /// The rotation of the camera, as read by the [`TransformAdapter`].
+    pub rotation: DQuat,
+    /// The projection of the camera. This is `None` for custom projections, which are left
+    /// unchanged during playback.
+    pub projection: Option<BookmarkProjection>,
+}
+
+/// A sequence of [`Keyframe`]s, ordered by time.
+///
+/// The camera translation follows a smooth spline through the keyframes, with velocities chosen so
+/// the camera moves smoothly through each keyframe at the timing given by the keyframe times. The
+/// rotation is interpolated with spherical quadrangle interpolation (squad), so the angular
+/// velocity is continuous through each keyframe.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlythroughPath {
+    keyframes: Vec<Keyframe>,
+}
+
+impl FlythroughPath {
+    /// The keyframes of this path, ordered by time.
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    /// Insert a keyframe, keeping the keyframes ordered by time.
+    pub fn insert(&mut self, keyframe: Keyframe) {
+        let index = self.keyframes.partition_point(|k| k.time <= keyframe.time);
+        self.keyframes.insert(index, keyframe);
+    }
+
+    /// Remove the keyframe at this index.
+    pub fn remove(&mut self, index: usize) -> Option<Keyframe> {
+        (index < self.keyframes.len()).then(|| self.keyframes.remove(index))
+    }
+
+    /// Remove all keyframes.
+    pub fn clear(&mut self) {
+        self.keyframes.clear();
+    }
+
+    /// Capture the current pose of a camera as a keyframe at `time` seconds. Returns `false` if the
+    /// pose of the camera could not be read.
+    pub fn capture(
+        &mut self,
+        camera: &EntityRef,
+        transform_adapter: &TransformAdapter,
+        time: f64,
+    ) -> bool {
+        let Some((translation, rotation)) = transform_adapter.read(camera) else {
+            return false;
+        };
+        self.insert(Keyframe {
+            time,
+            translation,
+            rotation,
+            projection: camera
+                .get::<Projection>()
+                .and_then(BookmarkProjection::capture),
+        });
+        true
+    }
+
+    /// The time of the last keyframe, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.keyframes.last().map_or(0.0, |k| k.time)
+    }
+
+    /// Sample the path at `time` seconds. Times outside of the path are clamped to the first or
+    /// last keyframe.
+    pub fn sample(&self, time: f64) -> Option<Keyframe> {
+        let keys = &self.keyframes;
+        let (first, last) = (keys.first()?, keys.last()?);
+        if time <= first.time || keys.len() == 1 {
+            return Some(*first);
+        }
+        if time >= last.time {
+            return Some(*last);
+        }
+
+        let i = keys.partition_point(|k| k.time <= time) - 1;
+        let (k0, k1) = (&keys[i], &keys[i + 1]);
+        let span = k1.time - k0.time;
+        let s = if span > 0.0 {
+            (time - k0.time) / span
+        } else {
+            1.0
+        };
+
+        // Velocity at each keyframe, from its neighbors, like a Catmull-Rom spline that respects
+        // uneven keyframe timing.
+        let velocity = |j: usize| {
+            let (a, b) = (
+                &keys[j.saturating_sub(1)],
+                &keys[(j + 1).min(keys.len() - 1)],
+            );
+            let dt = b.time - a.time;
+            if dt > 0.0 {
+                (b.translation - a.translation) / dt
+            } else {
+                DVec3::ZERO
+            }
+        };
+        let (s2, s3) = (s * s, s * s * s);
+        let translation = k0.translation * (2.0 * s3 - 3.0 * s2 + 1.0)
+            + velocity(i) * span * (s3 - 2.0 * s2 + s)
+            + k1.translation * (-2.0 * s3 + 3.0 * s2)
+            + velocity(i + 1) * span * (s3 - s2);
+
+        let projection = match (k0.projection, k1.projection) {
+            (
+                Some(BookmarkProjection::Perspective { fov: a, near, far }),
+                Some(BookmarkProjection::Perspective { fov: b, .. }),
+            ) => Some(BookmarkProjection::Perspective {
+                fov: a + (b - a) * s as f32,
+                near,
+                far,
+            }),
+            (
+                Some(BookmarkProjection::Orthographic {
+                    scale: a,
+                    near,
+                    far,
+                    viewport_origin,
+                    scaling_mode,
+                }),
+                Some(BookmarkProjection::Orthographic { scale: b, .. }),
+            ) => Some(BookmarkProjection::Orthographic {
+                scale: a + (b - a) * s as f32,
+                near,
+                far,
+                viewport_origin,
+                scaling_mode,
+            }),
+            (a, _) => a,
+        };
+
+        // Rotations are flipped into the same hemisphere as their neighbor, so each segment takes
+        // the shortest path.
+        let neighbor = |j: usize, reference: DQuat| {
+            let rotation = keys[j.min(keys.len() - 1)].rotation;
+            if rotation.dot(reference) < 0.0 {
+                -rotation
+            } else {
+                rotation
+            }
+        };
+        let q1 = k0.rotation;
+        let q0 = neighbor(i.saturating_sub(1), q1);
+        let q2 = neighbor(i + 1, q1);
+        let q3 = neighbor(i + 2, q2);
+        let (a1, a2) = (squad_control(q0, q1, q2), squad_control(q1, q2, q3));
+        let rotation = q1
+            .slerp(q2, s)
+            .slerp(a1.slerp(a2, s), 2.0 * s * (1.0 - s))
+            .normalize();
+
+        Some(Keyframe {
+            time,
+            translation,
+            rotation,
+            projection,
+        })
+    }
+
+    /// Serialize the path to a RON string.
+    pub fn to_ron(&self) -> Result<String, FlythroughError> {
+        ron::ser::to_string_pretty(self, Default::default()).map_err(FlythroughError::Serialize)
+    }
+
+    /// Deserialize a path from a RON string.
+    pub fn from_ron(ron: &str) -> Result<Self, FlythroughError> {
+        let mut path: Self = ron::from_str(ron).map_err(FlythroughError::Deserialize)?;
+        path.keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+        Ok(path)
+    }
+
+    /// Save the path to a RON file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), FlythroughError> {
+        std::fs::write(path, self.to_ron()?).map_err(FlythroughError::Io)
+    }
+
+    /// Load a path from a RON file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FlythroughError> {
+        Self::from_ron(&std::fs::read_to_string(path).map_err(FlythroughError::Io)?)
+    }
+}
+
+/// The inner control point of a squad segment at `q1`, between its neighbors `q0` and `q2`.
+fn squad_control(q0: DQuat, q1: DQuat, q2: DQuat) -> DQuat {
+    let inverse = q1.inverse();
+    let log = |q: DQuat| (inverse * q).to_scaled_axis();
+    q1 * DQuat::from_scaled_axis(-(log(q2) + log(q0)) / 4.0)
+}
+
+/// An error encountered while saving or loading a [`FlythroughPath`].
+#[derive(Debug)]
+pub enum FlythroughError {
+    /// The file could not be read or written.
+    Io(std::io::Error),
+    /// The path could not be serialized.
+    Serialize(ron::Error),
+    /// The file does not contain a valid path.
+    Deserialize(ron::error::SpannedError),
+}
+
+impl Display for FlythroughError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlythroughError::Io(err) => write!(f, "Unable to access flythrough file: {err}"),
+            FlythroughError::Serialize(err) => write!(f, "Unable to serialize flythrough: {err}"),
+            FlythroughError::Deserialize(err) => {
+                write!(f, "Unable to deserialize flythrough: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FlythroughError {}
+
+/// The playback state of a [`FlythroughPlayer`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaybackState {
+    /// The path is not playing.
+    #[default]
+    Stopped,
+    /// The path is playing.
+    Playing,
+    /// The path is paused, and will continue from the current time when played.
+    Paused,
+}
+
+/// Optional. Plays a [`FlythroughPath`] on an [`EditorCam`].
+///
+/// User control of the camera is restored when playback stops, and when this component is removed
+/// or replaced.
+#[derive(Debug, Clone, Component)]
+#[component(on_replace = Self::on_replace)]
+pub struct FlythroughPlayer {
+    /// The path to play.
+    pub path: FlythroughPath,
+    /// The playback speed multiplier. A speed of `2.0` plays the path twice as fast.
+    pub speed: f64,
+    /// Should playback restart from the beginning when the end of the path is reached?
+    pub looping: bool,
+    time: f64,
+    state: PlaybackState,
+    initial_enabled: Option<EnabledMotion>,
+}
+
+impl FlythroughPlayer {
+    /// Create a player for this path.
+    pub fn new(path: FlythroughPath) -> Self {
+        Self {
+            path,
+            speed: 1.0,
+            looping: false,
+            time: 0.0,
+            state: PlaybackState::Stopped,
+            initial_enabled: None,
+        }
+    }
+
+    /// Loop playback of the path.
+    pub fn looping(self) -> Self {
+        Self {
+            looping: true,
+            ..self
+        }
+    }
+
+    /// Start or resume playback from the current time. If playback stopped at the end of the path,
+    /// it restarts from the beginning.
+    pub fn play(&mut self) {
+        if self.state == PlaybackState::Stopped && self.time >= self.path.duration() {
+            self.time = 0.0;
+        }
+        self.state = PlaybackState::Playing;
+    }
+
+    /// Pause playback at the current time.
+    pub fn pause(&mut self) {
+        if self.state == PlaybackState::Playing {
+            self.state = PlaybackState::Paused;
+        }
+    }
+
+    /// Stop playback and rewind to the beginning, returning control of the camera to the user.
+    pub fn stop(&mut self) {
+        self.state = PlaybackState::Stopped;
+        self.time = 0.0;
+    }
+
+    /// Jump to a time along the path, in seconds.
+    pub fn seek(&mut self, time: f64) {
+        self.time = time.clamp(0.0, self.path.duration());
+    }
+
+    /// The current playback time, in seconds.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// The current playback state.
+    pub fn state(&self) -> PlaybackState {
+        self.state
+    }
+
+    /// Restore user control if this player was removed or replaced while it was playing.
+    fn on_replace(mut world: DeferredWorld, context: HookContext) {
+        let Some(enabled) = world
+            .get_mut::<FlythroughPlayer>(context.entity)
+            .and_then(|mut player| player.initial_enabled.take())
+        else {
+            return;
+        };
+        if let Some(mut controller) = world.get_mut::<EditorCam>(context.entity) {
+            controller.enabled_motion = enabled;
+        }
+    }
+
+    fn update(
+        mut cameras: Query<EntityMut, (With<EditorCam>, With<FlythroughPlayer>)>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        transform_adapter: Res<TransformAdapter>,
+        time: Res<Time>,
+    ) {
+        for mut camera_mut in &mut cameras {
+            let Some(mut player) = camera_mut.get_mut::<FlythroughPlayer>() else {
+                continue;
+            };
+            let is_playing = player.state == PlaybackState::Playing;
+            let newly_playing = is_playing && player.initial_enabled.is_none();
+            let restore = if !is_playing && player.initial_enabled.is_some() {
+                player.initial_enabled.take()
+            } else {
+                None
+            };
+            let sample = if is_playing {
+                let duration = player.path.duration();
+                player.time += time.delta_secs_f64() * player.speed;
+                if player.time >= duration {
+                    if player.looping && duration > 0.0 {
+                        player.time %= duration;
+                    } else {
+                        player.time = duration;
+                        player.state = PlaybackState::Stopped;
+                    }
+                }
+                player.path.sample(player.time)
+            } else {
+                None
+            };
+
+            let Some(mut controller) = camera_mut.get_mut::<EditorCam>() else {
+                continue;
+            };
+            if let Some(enabled) = restore {
+                controller.enabled_motion = enabled;
+            }
+            if !is_playing {
+                continue;
+            }
+            if newly_playing {
+                let enabled = controller.enabled_motion.clone();
+                controller.end_move();
+                controller.current_motion = motion::CurrentMotion::Stationary;
+                controller.enabled_motion = EnabledMotion {
+                    pan: false,
+                    orbit: false,
+                    zoom: false,
+                };
+                if let Some(mut player) = camera_mut.get_mut::<FlythroughPlayer>() {
+                    player.initial_enabled = Some(enabled);
+                }
+            }
+            redraw.write(RequestRedraw);
+
+            let Some(keyframe) = sample else {
+                continue;
+            };
+            let Some((camera_translation, camera_rotation)) =
+                transform_adapter.read(&camera_mut.as_readonly())
+            else {
+                continue;
+            };
+            let (_, delta_rotation, delta_translation) = {
+                let original =
+                    DAffine3::from_rotation_translation(camera_rotation, camera_translation);
+                let new =
+                    DAffine3::from_rotation_translation(keyframe.rotation, keyframe.translation);
+                (original.inverse() * new).to_scale_rotation_translation()
+            };
+            transform_adapter.apply_delta(&mut camera_mut, delta_translation, delta_rotation);
+
+            if let (Some(target), Some(mut projection)) =
+                (keyframe.projection, camera_mut.get_mut::<Projection>())
+            {
+                target.apply(&mut projection);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_transform::prelude::*;
+
+    use super::*;
+
+    fn path() -> FlythroughPath {
+        let mut path = FlythroughPath::default();
+        let keyframes = [
+            (0.0, DVec3::ZERO, DQuat::IDENTITY),
+            (1.5, DVec3::new(4.0, 1.0, -2.0), DQuat::from_rotation_y(1.2)),
+            // The same rotation in the other hemisphere, which should not change the path.
+            (
+                2.0,
+                DVec3::new(6.0, 3.0, 1.0),
+                -DQuat::from_euler(bevy_math::EulerRot::YXZ, 2.0, -0.5, 0.1),
+            ),
+            (
+                4.0,
+                DVec3::new(-3.0, 0.0, 5.0),
+                DQuat::from_rotation_x(-0.7),
+            ),
+        ];
+        for (time, translation, rotation) in keyframes {
+            path.insert(Keyframe {
+                time,
+                translation,
+                rotation,
+                projection: Some(BookmarkProjection::Perspective {
+                    fov: 0.5 + time as f32 * 0.1,
+                    near: 0.1,
+                    far: 1000.0,
+                }),
+            });
+        }
+        path
+    }
+
+    fn assert_pose(sample: Keyframe, keyframe: &Keyframe) {
+        let offset = (sample.translation - keyframe.translation).length();
+        assert!(offset < 1e-9, "{offset} from keyframe at {}", keyframe.time);
+        let angle = sample.rotation.angle_between(keyframe.rotation);
+        assert!(angle < 1e-6, "{angle} from keyframe at {}", keyframe.time);
+    }
+
+    #[test]
+    fn passes_through_keyframes() {
+        let path = path();
+        for keyframe in path.keyframes() {
+            assert_pose(path.sample(keyframe.time).unwrap(), keyframe);
+        }
+        // The path is continuous on both sides of each keyframe.
+        for keyframe in &path.keyframes()[1..path.keyframes().len() - 1] {
+            for time in [keyframe.time - 1e-6, keyframe.time + 1e-6] {
+                let sample = path.sample(time).unwrap();
+                assert!((sample.translation - keyframe.translation).length() < 1e-4);
+                assert!(sample.rotation.angle_between(keyframe.rotation) < 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn clamps_outside_the_path() {
+        let path = path();
+        let (first, last) = (path.keyframes()[0], path.keyframes()[3]);
+        assert_pose(path.sample(-3.0).unwrap(), &first);
+        assert_pose(path.sample(path.duration() + 3.0).unwrap(), &last);
+        assert!(FlythroughPath::default().sample(1.0).is_none());
+    }
+
+    #[test]
+    fn ron_round_trip() {
+        let path = path();
+        let loaded = FlythroughPath::from_ron(&path.to_ron().unwrap()).unwrap();
+        assert_eq!(loaded.keyframes().len(), path.keyframes().len());
+        for (loaded, keyframe) in loaded.keyframes().iter().zip(path.keyframes()) {
+            assert_eq!(loaded.time, keyframe.time);
+            assert_eq!(loaded.translation, keyframe.translation);
+            assert_eq!(loaded.rotation, keyframe.rotation);
+            let (
+                Some(BookmarkProjection::Perspective { fov, near, far }),
+                Some(BookmarkProjection::Perspective {
+                    fov: saved_fov,
+                    near: saved_near,
+                    far: saved_far,
+                }),
+            ) = (loaded.projection, keyframe.projection)
+            else {
+                panic!("the projection was not loaded");
+            };
+            assert_eq!((fov, near, far), (saved_fov, saved_near, saved_far));
+        }
+    }
+
+    #[test]
+    fn restores_control_when_removed() {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<TransformAdapter>();
+        world.init_resource::<Messages<RequestRedraw>>();
+        let mut player = FlythroughPlayer::new(path());
+        player.play();
+        let entity = world
+            .spawn((EditorCam::default(), Transform::default(), player))
+            .id();
+
+        let is_enabled = |world: &World| {
+            let enabled = &world.get::<EditorCam>(entity).unwrap().enabled_motion;
+            [enabled.pan, enabled.orbit, enabled.zoom]
+        };
+        world.run_system_once(FlythroughPlayer::update).unwrap();
+        assert_eq!(is_enabled(&world), [false; 3]);
+
+        // Replacing the player while it is playing.
+        world
+            .entity_mut(entity)
+            .insert(FlythroughPlayer::new(path()));
+        assert_eq!(is_enabled(&world), [true; 3]);
+
+        world.get_mut::<FlythroughPlayer>(entity).unwrap().play();
+        world.run_system_once(FlythroughPlayer::update).unwrap();
+        assert_eq!(is_enabled(&world), [false; 3]);
+        world.entity_mut(entity).remove::<FlythroughPlayer>();
+        assert_eq!(is_enabled(&world), [true; 3]);
+    }
+}
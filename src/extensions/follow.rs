@@ -0,0 +1,252 @@
+//! A `bevy_editor_cam` extension that makes a camera follow a moving entity, like a vehicle or the
+//! end effector of a robot arm in a simulation, while the user can still orbit, pan, and zoom
+//! around it.
+//!
+//! To use it, add a [`Follow`] component to a camera, and add the [`FollowPlugin`] to your app.
+//! Every frame, the camera is moved by the distance the target moved, so the view of the target
+//! does not change. Anchors on the target stay attached to it while the user is moving the camera.
+//!
+//! The position of the target is read from its [`GlobalTransform`], before the camera is moved in
+//! [`PreUpdate`]. Targets moved in [`Update`] are followed on the next frame, after their
+//! [`GlobalTransform`] has been updated.
+
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{DQuat, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_time::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_window::RequestRedraw;
+
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct FollowPlugin;
+
+impl Plugin for FollowPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Follow::update
+                .before(super::tween::Tweens::update)
+                .before(super::collision::CollisionAvoidance::record_positions)
+                .before(crate::controller::component::EditorCam::update_camera_positions)
+                .in_set(crate::SyncCameraPosition),
+        );
+    }
+}
+
+/// Optional. Moves an [`EditorCam`] with a target entity. See the [module](self) docs.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct Follow {
+    /// Should the camera follow the target?
+    pub enabled: bool,
+    /// The entity to follow.
+    pub target: Entity,
+    /// How long the camera lags behind the target. The camera covers about 63% of the remaining
+    /// distance to the target in this time. When zero, the camera moves with the target exactly.
+    pub smoothing: Duration,
+    /// How far the target can move before the camera follows it. The camera only follows the
+    /// motion of the target that takes it outside a sphere with this radius, centered on the point
+    /// the camera is following.
+    pub dead_zone: f64,
+    /// When the camera starts following a target, set [`EditorCam::last_anchor_depth`] to the
+    /// depth of the target, so motions started without a hit, like zooming into empty space, use
+    /// the depth of the target. Later anchors, like a hit picked by the user, are kept.
+    pub anchor_depth: bool,
+    /// The target being followed, and the point the camera is following, in the same space as the
+    /// translation read by the [`TransformAdapter`].
+    #[reflect(ignore)]
+    followed: Option<(Entity, DVec3)>,
+}
+
+impl Follow {
+    /// Follow `target` exactly, without smoothing or a dead zone.
+    pub fn new(target: Entity) -> Self {
+        Self {
+            enabled: true,
+            target,
+            smoothing: Duration::ZERO,
+            dead_zone: 0.0,
+            anchor_depth: true,
+            followed: None,
+        }
+    }
+
+    /// Set how long the camera lags behind the target.
+    pub fn with_smoothing(self, smoothing: Duration) -> Self {
+        Self { smoothing, ..self }
+    }
+
+    /// Set how far the target can move before the camera follows it.
+    pub fn with_dead_zone(self, dead_zone: f64) -> Self {
+        Self { dead_zone, ..self }
+    }
+
+    /// The point the camera is following, in the same space as the translation read by the
+    /// [`TransformAdapter`]. This lags behind the target when using smoothing or a dead zone.
+    pub fn followed_point(&self) -> Option<DVec3> {
+        self.followed.map(|(_, point)| point)
+    }
+
+    /// The camera motion needed to follow the target at `target_position`, this frame.
+    fn follow(&mut self, target_position: DVec3, delta_time: Duration) -> DVec3 {
+        let followed = match self.followed {
+            Some((entity, followed)) if entity == self.target => followed,
+            // Start following from the current position, so the camera does not jump.
+            _ => {
+                self.followed = Some((self.target, target_position));
+                return DVec3::ZERO;
+            }
+        };
+
+        let offset = target_position - followed;
+        let distance = offset.length();
+        let dead_zone = self.dead_zone.max(0.0);
+        if distance <= dead_zone || !distance.is_finite() {
+            return DVec3::ZERO;
+        }
+        let outside_dead_zone = offset * (distance - dead_zone) / distance;
+        let factor = if self.smoothing.is_zero() {
+            1.0
+        } else {
+            1.0 - (-delta_time.as_secs_f64() / self.smoothing.as_secs_f64()).exp()
+        };
+        let motion = outside_dead_zone * factor;
+        self.followed = Some((self.target, followed + motion));
+        motion
+    }
+
+    fn update(
+        mut camera_set: ParamSet<(
+            Query<(Entity, &Follow, &GlobalTransform), With<EditorCam>>,
+            Query<&GlobalTransform>,
+            Query<EntityMut, (With<EditorCam>, With<Follow>)>,
+        )>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        transform_adapter: Res<TransformAdapter>,
+        time: Res<Time>,
+    ) {
+        let follows = camera_set
+            .p0()
+            .iter()
+            .map(|(camera, follow, camera_transform)| {
+                (
+                    camera,
+                    follow.enabled.then_some(follow.target),
+                    camera_transform.translation().as_dvec3(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for (camera, target, camera_world) in follows {
+            // Make the target position relative to the camera, then move it into the space of the
+            // transform adapter, which differs from world space when using a floating origin.
+            let target_relative = target
+                .and_then(|target| camera_set.p1().get(target).ok().copied())
+                .map(|target_transform| target_transform.translation().as_dvec3() - camera_world);
+
+            let mut cameras = camera_set.p2();
+            let Ok(mut camera_mut) = cameras.get_mut(camera) else {
+                continue;
+            };
+            let Some((camera_translation, camera_rotation)) =
+                transform_adapter.read(&camera_mut.as_readonly())
+            else {
+                continue;
+            };
+            let Some(mut follow) = camera_mut.get_mut::<Follow>() else {
+                continue;
+            };
+            let Some(target_relative) = target_relative else {
+                // Disabled, or the target does not exist. Start over when it does.
+                follow.followed = None;
+                continue;
+            };
+            let started = follow.followed.map(|(entity, _)| entity) != Some(follow.target);
+            let motion = follow.follow(camera_translation + target_relative, time.delta());
+            let anchor_depth = follow.anchor_depth && started;
+
+            if let Some(mut editor_cam) = camera_mut.get_mut::<EditorCam>() {
+                let target_depth = (camera_rotation.inverse() * (target_relative - motion)).z;
+                if anchor_depth && !editor_cam.current_motion.is_moving() && target_depth < 0.0 {
+                    editor_cam.last_anchor_depth = target_depth;
+                }
+            }
+
+            if motion == DVec3::ZERO || !motion.is_finite() {
+                continue;
+            }
+            let delta_translation = camera_rotation.inverse() * motion;
+            transform_adapter.apply_delta(&mut camera_mut, delta_translation, DQuat::IDENTITY);
+            redraw.write(RequestRedraw);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn starts_without_moving() {
+        let mut follow = Follow::new(Entity::PLACEHOLDER);
+        assert_eq!(follow.follow(DVec3::X, FRAME), DVec3::ZERO);
+        assert_eq!(follow.followed_point(), Some(DVec3::X));
+        assert_eq!(
+            follow.follow(DVec3::new(3.0, 0.0, 0.0), FRAME),
+            DVec3::X * 2.0
+        );
+    }
+
+    #[test]
+    fn dead_zone() {
+        let mut follow = Follow::new(Entity::PLACEHOLDER).with_dead_zone(1.0);
+        follow.follow(DVec3::ZERO, FRAME);
+        assert_eq!(follow.follow(DVec3::new(0.0, 0.5, 0.0), FRAME), DVec3::ZERO);
+        assert_eq!(
+            follow.follow(DVec3::new(0.0, -1.0, 0.0), FRAME),
+            DVec3::ZERO
+        );
+        assert_eq!(follow.followed_point(), Some(DVec3::ZERO));
+
+        // Only the motion outside of the dead zone is followed.
+        let motion = follow.follow(DVec3::new(0.0, 0.0, 3.0), FRAME);
+        assert!((motion - DVec3::new(0.0, 0.0, 2.0)).length() < 1e-12);
+        assert_eq!(follow.follow(DVec3::new(0.0, 0.0, 3.0), FRAME), DVec3::ZERO);
+    }
+
+    #[test]
+    fn smoothing_converges() {
+        let smoothing = Duration::from_millis(100);
+        let mut follow = Follow::new(Entity::PLACEHOLDER).with_smoothing(smoothing);
+        follow.follow(DVec3::ZERO, FRAME);
+
+        let target = DVec3::new(10.0, 0.0, 0.0);
+        let first = follow.follow(target, smoothing);
+        let expected = 10.0 * (1.0 - (-1.0f64).exp());
+        assert!((first.x - expected).abs() < 1e-9, "{first}");
+
+        let mut total = first;
+        for _ in 0..200 {
+            let motion = follow.follow(target, FRAME);
+            assert!(motion.x >= 0.0, "overshot the target: {motion}");
+            total += motion;
+        }
+        assert!((total - target).length() < 1e-6, "{total}");
+        assert!((follow.followed_point().unwrap() - target).length() < 1e-6);
+    }
+
+    #[test]
+    fn restarts_when_the_target_changes() {
+        let mut follow = Follow::new(Entity::PLACEHOLDER);
+        follow.follow(DVec3::ZERO, FRAME);
+        follow.target = Entity::from_raw_u32(1).unwrap();
+        assert_eq!(follow.follow(DVec3::X * 5.0, FRAME), DVec3::ZERO);
+        assert_eq!(follow.followed_point(), Some(DVec3::X * 5.0));
+    }
+}
@@ -0,0 +1,242 @@
+//! A `bevy_editor_cam` extension that adds the ability to smoothly move the camera so that a set of
+//! entities, or some bounds, fill the viewport. This is commonly called "zoom to fit" or "frame
+//! selection".
+//!
+//! The view direction of the camera is not changed. The camera is moved so the bounds are centered
+//! in the viewport, and the anchor depth is set to the center of the bounds, so subsequent orbits
+//! rotate about the framed object.
+
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_camera::{prelude::*, primitives::Aabb};
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_window::RequestRedraw;
+
+use super::tween::{CameraTween, TweenPlugin, Tweens};
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct FramePlugin;
+
+impl Plugin for FramePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
+        app.init_resource::<Frame>()
+            .add_message::<FrameTrigger>()
+            .add_systems(
+                PostUpdate,
+                FrameTrigger::receive.after(bevy_transform::TransformSystems::Propagate),
+            );
+    }
+}
+
+/// The bounds that should fill the viewport when a [`FrameTrigger`] is sent.
+///
+/// Bounds are in the same space as the translation read by the [`TransformAdapter`]. With the
+/// default adapter, this is world space.
+#[derive(Debug, Clone)]
+pub enum FrameTarget {
+    /// Frame these entities. The bounds of each entity are computed from its [`Aabb`] and
+    /// [`GlobalTransform`], relative to the [`GlobalTransform`] of the camera. Entities without an
+    /// [`Aabb`] are treated as a point at their translation.
+    Entities(Vec<Entity>),
+    /// Frame an axis-aligned bounding box.
+    Aabb {
+        /// The minimum corner of the box.
+        min: DVec3,
+        /// The maximum corner of the box.
+        max: DVec3,
+    },
+    /// Frame a sphere.
+    Sphere {
+        /// The center of the sphere.
+        center: DVec3,
+        /// The radius of the sphere.
+        radius: f64,
+    },
+}
+
+impl FrameTarget {
+    /// Compute the bounding sphere of this target, as a center and radius.
+    fn bounding_sphere(
+        &self,
+        entities: &Query<(&GlobalTransform, Option<&Aabb>)>,
+    ) -> Option<(DVec3, f64)> {
+        match self {
+            FrameTarget::Entities(targets) => {
+                let (min, max) = entities
+                    .iter_many(targets)
+                    .flat_map(|(transform, aabb)| {
+                        let aabb = aabb.copied().unwrap_or_default();
+                        let (center, half) = (aabb.center, aabb.half_extents);
+                        [-1.0, 1.0].into_iter().flat_map(move |x| {
+                            [-1.0, 1.0].into_iter().flat_map(move |y| {
+                                [-1.0, 1.0].into_iter().map(move |z| {
+                                    let corner = center + half * Vec3A::new(x, y, z);
+                                    transform.transform_point(corner.into()).as_dvec3()
+                                })
+                            })
+                        })
+                    })
+                    .fold(None, |acc: Option<(DVec3, DVec3)>, p| match acc {
+                        Some((min, max)) => Some((min.min(p), max.max(p))),
+                        None => Some((p, p)),
+                    })?;
+                Some(((min + max) / 2.0, (max - min).length() / 2.0))
+            }
+            FrameTarget::Aabb { min, max } => {
+                Some(((*min + *max) / 2.0, (*max - *min).length() / 2.0))
+            }
+            FrameTarget::Sphere { center, radius } => Some((*center, radius.abs())),
+        }
+        .filter(|(center, radius)| center.is_finite() && radius.is_finite())
+    }
+}
+
+/// Send this event to smoothly move the camera so the target fills the viewport. Animation speed
+/// and the margin around the target are configured with the [`Frame`] resource.
+#[derive(Debug, Clone, Message)]
+pub struct FrameTrigger {
+    /// The bounds to frame.
+    pub target: FrameTarget,
+    /// The camera to move.
+    pub camera: Entity,
+}
+
+impl FrameTrigger {
+    fn receive(
+        mut events: MessageReader<Self>,
+        state: Res<Frame>,
+        mut tweens: ResMut<Tweens>,
+        mut camera_set: ParamSet<(
+            Query<(&Camera, &Projection, &mut EditorCam)>,
+            Query<EntityRef, With<EditorCam>>,
+        )>,
+        entities: Query<(&GlobalTransform, Option<&Aabb>)>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        let margin = state.margin.max(0.0);
+        for event in events.read() {
+            let Some((center, radius)) = event.target.bounding_sphere(&entities) else {
+                continue;
+            };
+            let camera_refs = camera_set.p1();
+            let Ok(camera_ref) = camera_refs.get(event.camera) else {
+                continue;
+            };
+            let Some((camera_translation, camera_rotation)) = transform_adapter.read(&camera_ref)
+            else {
+                continue;
+            };
+            let center = match (&event.target, camera_ref.get::<GlobalTransform>()) {
+                // Entity bounds are in world space. Make them relative to the camera, then move
+                // them into the space of the transform adapter, which differs from world space when
+                // using a floating origin.
+                (FrameTarget::Entities(_), Some(camera_global)) => {
+                    center - camera_global.translation().as_dvec3() + camera_translation
+                }
+                _ => center,
+            };
+            let mut cameras = camera_set.p0();
+            let Ok((camera, projection, mut controller)) = cameras.get_mut(event.camera) else {
+                continue;
+            };
+            let Some(viewport) = camera.logical_viewport_size() else {
+                continue;
+            };
+            let aspect = viewport.x as f64 / viewport.y as f64;
+            let radius = radius * (1.0 + margin);
+            let has_extent = radius > f64::EPSILON;
+
+            let (distance, target_scale) = match projection {
+                Projection::Perspective(perspective) => {
+                    let half_fov_y = perspective.fov as f64 / 2.0;
+                    let half_fov_x = (half_fov_y.tan() * aspect).atan();
+                    let half_fov = half_fov_x.min(half_fov_y);
+                    let distance = if has_extent {
+                        radius / half_fov.sin()
+                    } else {
+                        controller.last_anchor_depth().abs()
+                    };
+                    (distance, None)
+                }
+                Projection::Orthographic(ortho) => {
+                    // The aspect of the projection differs from the viewport with some scaling
+                    // modes, like `ScalingMode::Fixed`.
+                    let size = crate::controller::zoom::ortho_unscaled_size(
+                        viewport.as_dvec2(),
+                        &ortho.scaling_mode,
+                    );
+                    let ortho_aspect = size.x / size.y;
+                    // The visible half height needed for the sphere to fit in both dimensions.
+                    let half_height = radius * ortho_aspect.recip().max(1.0);
+                    let scale_factor =
+                        crate::controller::zoom::ortho_tri_base_to_scale_factor(camera, ortho);
+                    let scale = has_extent.then_some((half_height * scale_factor) as f32);
+                    // Keep the whole sphere in front of the camera, the orthographic projection
+                    // update will handle the near clipping plane.
+                    (radius.max(controller.last_anchor_depth().abs()), scale)
+                }
+                Projection::Custom(_) => {
+                    bevy_log::error_once!("Framing is not supported for custom projections.");
+                    continue;
+                }
+            };
+            if !distance.is_finite() {
+                continue;
+            }
+
+            let camera_forward = camera_rotation * DVec3::NEG_Z;
+            let target_translation = center - camera_forward * distance;
+
+            redraw.write(RequestRedraw);
+
+            let initial_scale = match projection {
+                Projection::Orthographic(ortho) => Some(ortho.scale),
+                _ => None,
+            };
+            let tween = CameraTween {
+                translation: Some(target_translation - camera_translation),
+                ortho_scale: initial_scale.zip(target_scale),
+                focus: Some(center),
+                ..CameraTween::new(Frame::TWEEN_LABEL)
+            }
+            .with_animation(state.animation_duration, state.animation_curve);
+            tweens.start(event.camera, &mut controller, tween);
+        }
+    }
+}
+
+/// Stores settings for the frame plugin.
+#[derive(Resource, Reflect)]
+pub struct Frame {
+    /// Extra space around the framed bounds, as a fraction of their radius.
+    pub margin: f64,
+    /// The duration of the framing animation.
+    pub animation_duration: Duration,
+    /// The cubic curve used to animate the camera while framing.
+    #[reflect(ignore)]
+    pub animation_curve: CubicSegment<Vec2>,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            margin: 0.1,
+            animation_duration: Duration::from_millis(400),
+            animation_curve: CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
+        }
+    }
+}
+
+impl Frame {
+    /// The [`CameraTween::label`] of framing animations.
+    pub const TWEEN_LABEL: &'static str = "frame";
+}
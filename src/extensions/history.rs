@@ -0,0 +1,249 @@
+//! A `bevy_editor_cam` extension that records a history of camera views, allowing users to go back
+//! to a previous view after an accidental flick, and forward again.
+//!
+//! A view is recorded every time the camera settles, that is, when the camera is
+//! [`Stationary`](motion::CurrentMotion::Stationary) and has stopped moving. This happens when
+//! momentum ends, and when animations like [`LookTo`](super::look_to::LookTo) complete. Send a
+//! [`ViewHistoryTrigger`] to move back or forward through the history.
+//!
+//! Requires the `extension_history` feature.
+
+use std::{collections::VecDeque, time::Duration};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DQuat, DVec3};
+use bevy_platform::collections::HashMap;
+use bevy_reflect::prelude::*;
+use bevy_window::RequestRedraw;
+
+use super::{
+    bookmarks::{BookmarkProjection, ViewBookmark},
+    tween::{CameraTween, TweenPlugin, Tweens},
+};
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct ViewHistoryPlugin;
+
+impl Plugin for ViewHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
+        app.init_resource::<ViewHistory>()
+            .add_message::<ViewHistoryTrigger>()
+            .add_systems(
+                PreUpdate,
+                ViewHistory::record
+                    .after(crate::controller::component::EditorCam::update_camera_positions)
+                    .in_set(crate::SyncCameraPosition),
+            )
+            .add_systems(PostUpdate, ViewHistoryTrigger::receive);
+    }
+}
+
+/// Send this event to animate the camera to the previous or next view in its history.
+#[derive(Debug, Clone, Copy, Message)]
+pub enum ViewHistoryTrigger {
+    /// Go back to the previous view.
+    Back {
+        /// The camera to move.
+        camera: Entity,
+    },
+    /// Go forward to the next view, after going back.
+    Forward {
+        /// The camera to move.
+        camera: Entity,
+    },
+}
+
+impl ViewHistoryTrigger {
+    fn receive(
+        mut events: MessageReader<Self>,
+        mut history: ResMut<ViewHistory>,
+        mut tweens: ResMut<Tweens>,
+        mut cameras: Query<EntityMut, With<EditorCam>>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        for event in events.read() {
+            let (camera, step) = match *event {
+                ViewHistoryTrigger::Back { camera } => (camera, -1),
+                ViewHistoryTrigger::Forward { camera } => (camera, 1),
+            };
+            let Some(camera_history) = history.map.get_mut(&camera) else {
+                continue;
+            };
+            let Some(cursor) = camera_history
+                .cursor
+                .checked_add_signed(step)
+                .filter(|cursor| *cursor < camera_history.entries.len())
+            else {
+                continue;
+            };
+            let Ok(mut camera_mut) = cameras.get_mut(camera) else {
+                continue;
+            };
+            let view = camera_history.entries[cursor].view;
+            let tween = CameraTween::new(ViewHistory::TWEEN_LABEL)
+                .with_animation(history.animation_duration, history.animation_curve);
+            if view.transition(&mut tweens, &mut camera_mut, &transform_adapter, tween) {
+                if let Some(camera_history) = history.map.get_mut(&camera) {
+                    camera_history.cursor = cursor;
+                }
+                redraw.write(RequestRedraw);
+            }
+        }
+    }
+}
+
+struct HistoryEntry {
+    /// The latest view recorded in this entry.
+    view: ViewBookmark,
+    /// The view when this entry was first recorded. New views are compared to this, so that many
+    /// small adjustments can not drift a single entry arbitrarily far.
+    first: ViewBookmark,
+}
+
+#[derive(Default)]
+struct CameraHistory {
+    entries: VecDeque<HistoryEntry>,
+    cursor: usize,
+    last_pose: Option<(DVec3, DQuat)>,
+    settled: bool,
+}
+
+impl CameraHistory {
+    /// Record a settled view, replacing the current entry if the camera barely moved.
+    fn record(&mut self, view: ViewBookmark, coalesce: bool, max_depth: usize) {
+        if let Some(current) = self.entries.get_mut(self.cursor) {
+            if coalesce {
+                current.view = view;
+                return;
+            }
+            self.entries.truncate(self.cursor + 1);
+        }
+        self.entries.push_back(HistoryEntry { view, first: view });
+        while self.entries.len() > max_depth.max(1) {
+            self.entries.pop_front();
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+}
+
+/// Stores settings and state for the view history plugin.
+#[derive(Resource, Reflect)]
+pub struct ViewHistory {
+    /// The maximum number of views remembered for each camera. The oldest views are forgotten
+    /// first.
+    pub max_depth: usize,
+    /// Views closer together than this are merged into a single history entry, as a fraction of
+    /// the anchor depth. This prevents tiny adjustments from filling up the history.
+    pub coalesce_distance: f64,
+    /// Views with rotations closer together than this angle, in radians, are merged into a single
+    /// history entry.
+    pub coalesce_angle: f64,
+    /// The duration of the transition animation when moving through the history.
+    pub animation_duration: Duration,
+    /// The cubic curve used to animate the camera when moving through the history.
+    #[reflect(ignore)]
+    pub animation_curve: CubicSegment<Vec2>,
+    #[reflect(ignore)]
+    map: HashMap<Entity, CameraHistory>,
+}
+
+impl Default for ViewHistory {
+    fn default() -> Self {
+        Self {
+            max_depth: 50,
+            coalesce_distance: 0.01,
+            coalesce_angle: 1f64.to_radians(),
+            animation_duration: Duration::from_millis(600),
+            animation_curve: CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
+            map: Default::default(),
+        }
+    }
+}
+
+impl ViewHistory {
+    /// The [`CameraTween::label`] of history transitions.
+    pub const TWEEN_LABEL: &'static str = "view_history";
+
+    /// Forget the view history of this camera.
+    pub fn clear(&mut self, camera: Entity) {
+        self.map.remove(&camera);
+    }
+
+    /// The number of views recorded for this camera.
+    pub fn len(&self, camera: Entity) -> usize {
+        self.map.get(&camera).map_or(0, |h| h.entries.len())
+    }
+
+    /// Returns `true` if there is a previous view this camera can go back to.
+    pub fn can_go_back(&self, camera: Entity) -> bool {
+        self.map.get(&camera).is_some_and(|h| h.cursor > 0)
+    }
+
+    /// Returns `true` if there is a next view this camera can go forward to.
+    pub fn can_go_forward(&self, camera: Entity) -> bool {
+        self.map
+            .get(&camera)
+            .is_some_and(|h| h.cursor + 1 < h.entries.len())
+    }
+
+    fn is_similar(&self, a: &ViewBookmark, b: &ViewBookmark) -> bool {
+        let scale = a.anchor_depth.abs().max(b.anchor_depth.abs());
+        let same_projection = match (a.projection, b.projection) {
+            (
+                Some(BookmarkProjection::Perspective { fov: a, .. }),
+                Some(BookmarkProjection::Perspective { fov: b, .. }),
+            ) => a == b,
+            (
+                Some(BookmarkProjection::Orthographic { scale: a, .. }),
+                Some(BookmarkProjection::Orthographic { scale: b, .. }),
+            ) => a == b,
+            (a, b) => a.is_none() && b.is_none(),
+        };
+        same_projection
+            && a.translation.distance(b.translation) <= self.coalesce_distance * scale
+            && a.rotation.angle_between(b.rotation) <= self.coalesce_angle
+    }
+
+    fn record(
+        mut history: ResMut<Self>,
+        cameras: Query<EntityRef, With<EditorCam>>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        history.map.retain(|camera, _| cameras.contains(*camera));
+
+        for camera in &cameras {
+            let Some(editor_cam) = camera.get::<EditorCam>() else {
+                continue;
+            };
+            let pose = transform_adapter.read(&camera);
+            let camera_history = history.map.entry(camera.id()).or_default();
+            let settled = pose.is_some()
+                && pose == camera_history.last_pose
+                && matches!(editor_cam.current_motion, motion::CurrentMotion::Stationary);
+            let just_settled = settled && !camera_history.settled;
+            camera_history.last_pose = pose;
+            camera_history.settled = settled;
+            if !just_settled {
+                continue;
+            }
+            let Some(view) = ViewBookmark::capture(&camera, &transform_adapter) else {
+                continue;
+            };
+            let coalesce = history
+                .map
+                .get(&camera.id())
+                .and_then(|h| h.entries.get(h.cursor))
+                .is_some_and(|current| history.is_similar(&current.first, &view));
+            let max_depth = history.max_depth;
+            if let Some(camera_history) = history.map.get_mut(&camera.id()) {
+                camera_history.record(view, coalesce, max_depth);
+            }
+        }
+    }
+}
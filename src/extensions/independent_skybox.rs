@@ -8,14 +8,14 @@
 
 use bevy_app::prelude::*;
 use bevy_asset::Handle;
-use bevy_camera::{prelude::*, visibility::RenderLayers};
+use bevy_camera::{prelude::*, visibility::RenderLayers, CameraUpdateSystems};
 use bevy_core_pipeline::Skybox;
 use bevy_ecs::prelude::*;
 use bevy_image::Image;
 use bevy_math::Quat;
 use bevy_reflect::prelude::*;
 use bevy_render::view::{Hdr, Msaa};
-use bevy_transform::prelude::*;
+use bevy_transform::{prelude::*, TransformSystems};
 
 /// See the [module](self) docs.
 pub struct IndependentSkyboxPlugin;
@@ -27,10 +27,14 @@ impl Plugin for IndependentSkyboxPlugin {
             (
                 IndependentSkyboxCamera::spawn,
                 IndependentSkyboxCamera::despawn,
-                ApplyDeferred,
-                IndependentSkyboxCamera::update,
             )
                 .chain(),
+        )
+        .add_systems(
+            PostUpdate,
+            IndependentSkyboxCamera::update
+                .after(TransformSystems::Propagate)
+                .before(CameraUpdateSystems),
         );
     }
 }
@@ -44,8 +48,8 @@ impl Plugin for IndependentSkyboxPlugin {
 /// 1. The camera you add the [`IndependentSkybox`] component to. This should be a camera controller, and can use any
 ///    projection you like.
 /// 2. A separate camera that is created automatically to render the skybox. This camera will
-///    copy the position and orientation of the first camera, but will use its own field of
-///    view, as specified by the [`IndependentSkybox::fov`] setting. This camera will have the
+///    copy the orientation of the first camera, but will use its own field of view, as specified
+///    by the [`IndependentSkybox::fov`] setting. This camera will have the
 ///    [`IndependentSkyboxCamera`] component.
 ///
 /// This struct controls the parameters used to render the skybox.
@@ -185,24 +189,36 @@ impl IndependentSkyboxCamera {
         }
     }
 
-    /// Update the position and projection of this [`IndependentSkyboxCamera`] to copy the camera it
+    /// Update the orientation and projection of this [`IndependentSkyboxCamera`] to copy the camera it
     /// is following.
     pub fn update(
         mut editor_cams: Query<
-            (&IndependentSkybox, &Transform, &Projection, &Camera),
+            (&IndependentSkybox, &GlobalTransform, &Projection, &Camera),
             (
-                Or<(Changed<IndependentSkybox>, Changed<Transform>)>,
+                Or<(Changed<IndependentSkybox>, Changed<GlobalTransform>)>,
                 Without<Self>,
             ),
         >,
-        mut skybox_cams: Query<(Mut<Transform>, Mut<Projection>, Mut<Camera>), With<Self>>,
+        mut skybox_cams: Query<
+            (
+                Mut<Transform>,
+                Mut<GlobalTransform>,
+                Mut<Projection>,
+                Mut<Camera>,
+            ),
+            With<Self>,
+        >,
     ) {
         for (editor_cam, editor_transform, editor_projection, camera) in &mut editor_cams {
             let Some(skybox_entity) = editor_cam.skybox_cam else {
                 continue;
             };
-            let Ok((mut skybox_transform, mut skybox_projection, mut skybox_camera)) =
-                skybox_cams.get_mut(skybox_entity)
+            let Ok((
+                mut skybox_transform,
+                mut skybox_global_transform,
+                mut skybox_projection,
+                mut skybox_camera,
+            )) = skybox_cams.get_mut(skybox_entity)
             else {
                 continue;
             };
@@ -219,7 +235,12 @@ impl IndependentSkyboxCamera {
                 })
             }
 
-            *skybox_transform = *editor_transform;
+            // The skybox is infinitely far away, so only the rotation matters. The world rotation
+            // is used, so the skybox is correct for cameras with a parent. The global transform is
+            // set directly, because this runs after transform propagation, and floating origin
+            // plugins like `big_space` do not propagate transforms outside of their grids.
+            *skybox_transform = Transform::from_rotation(editor_transform.rotation());
+            *skybox_global_transform = GlobalTransform::from(*skybox_transform);
         }
     }
 }
@@ -5,11 +5,11 @@ use std::{f64::consts::PI, time::Duration};
 
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
-use bevy_math::{prelude::*, DAffine3, DQuat, DVec3};
-use bevy_platform::{collections::HashMap, time::Instant};
+use bevy_math::{prelude::*, DQuat, DVec3};
 use bevy_reflect::prelude::*;
 use bevy_window::RequestRedraw;
 
+use super::tween::{CameraTween, RotationPivot, TweenPlugin, TweenRotation, Tweens};
 use crate::prelude::*;
 
 /// See the [module](self) docs.
@@ -17,14 +17,12 @@ pub struct LookToPlugin;
 
 impl Plugin for LookToPlugin {
     fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
         app.init_resource::<LookTo>()
             .add_message::<LookToTrigger>()
-            .add_systems(
-                PreUpdate,
-                LookTo::update
-                    .before(crate::controller::component::EditorCam::update_camera_positions),
-            )
-            .add_systems(PostUpdate, LookToTrigger::receive); // In PostUpdate so we don't miss users sending this in Update. LookTo::update will catch the changes next frame.
+            .add_systems(PostUpdate, LookToTrigger::receive); // In PostUpdate so we don't miss users sending this in Update. The tween will catch the changes next frame.
     }
 }
 
@@ -93,7 +91,8 @@ impl LookToTrigger {
 impl LookToTrigger {
     fn receive(
         mut events: MessageReader<Self>,
-        mut state: ResMut<LookTo>,
+        state: Res<LookTo>,
+        mut tweens: ResMut<Tweens>,
         mut camera_set: ParamSet<(Query<&mut EditorCam>, Query<EntityRef, With<EditorCam>>)>,
         mut redraw: MessageWriter<RequestRedraw>,
         transform_adapter: Res<TransformAdapter>,
@@ -111,44 +110,21 @@ impl LookToTrigger {
                 continue;
             };
             redraw.write(RequestRedraw);
-            let camera_forward = camera_rotation * DVec3::NEG_Z;
-            let camera_up = camera_rotation * DVec3::Y;
-            state
-                .map
-                .entry(event.camera)
-                .and_modify(|e| {
-                    e.start = Instant::now();
-                    e.initial_facing_direction = camera_forward;
-                    e.initial_up_direction = camera_up;
-                    e.target_facing_direction = event.target_facing_direction;
-                    e.target_up_direction = event.target_up_direction;
-                    e.complete = false;
-                })
-                .or_insert(LookToEntry {
-                    start: Instant::now(),
-                    initial_facing_direction: camera_forward,
-                    initial_up_direction: camera_up,
-                    target_facing_direction: event.target_facing_direction,
-                    target_up_direction: event.target_up_direction,
-                    complete: false,
-                });
-
-            controller.end_move();
-            controller.current_motion = motion::CurrentMotion::Stationary;
+            let tween = CameraTween {
+                rotation: Some(TweenRotation {
+                    from: camera_rotation,
+                    to: look_to(event.target_facing_direction, event.target_up_direction),
+                    pivot: RotationPivot::Anchor,
+                }),
+                ..CameraTween::new(LookTo::TWEEN_LABEL)
+            }
+            .with_animation(state.animation_duration, state.animation_curve);
+            tweens.start(event.camera, &mut controller, tween);
         }
     }
 }
 
-struct LookToEntry {
-    start: Instant,
-    initial_facing_direction: DVec3,
-    initial_up_direction: DVec3,
-    target_facing_direction: DVec3,
-    target_up_direction: DVec3,
-    complete: bool,
-}
-
-/// Stores settings and state for the dolly zoom plugin.
+/// Stores settings for the look to plugin.
 #[derive(Resource, Reflect)]
 pub struct LookTo {
     /// The duration of the "look to" transition animation.
@@ -156,8 +132,6 @@ pub struct LookTo {
     /// The cubic curve used to animate the camera during a "look to".
     #[reflect(ignore)]
     pub animation_curve: CubicSegment<Vec2>,
-    #[reflect(ignore)]
-    map: HashMap<Entity, LookToEntry>,
 }
 
 impl Default for LookTo {
@@ -165,94 +139,11 @@ impl Default for LookTo {
         Self {
             animation_duration: Duration::from_millis(400),
             animation_curve: CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
-            map: Default::default(),
         }
     }
 }
 
 impl LookTo {
-    fn update(
-        mut state: ResMut<Self>,
-        mut camera_set: ParamSet<(
-            Query<&mut EditorCam>,
-            Query<EntityRef, With<EditorCam>>,
-            Query<EntityMut, With<EditorCam>>,
-        )>,
-        mut redraw: MessageWriter<RequestRedraw>,
-        transform_adapter: Res<TransformAdapter>,
-    ) {
-        let animation_duration = state.animation_duration;
-        let animation_curve = state.animation_curve;
-        for (
-            camera,
-            LookToEntry {
-                start,
-                initial_facing_direction,
-                initial_up_direction,
-                target_facing_direction,
-                target_up_direction,
-                complete,
-            },
-        ) in state.map.iter_mut()
-        {
-            let camera_refs = camera_set.p1();
-            let Ok(camera_ref) = camera_refs.get(*camera) else {
-                continue;
-            };
-            let Some((mut camera_translation, mut camera_rotation)) =
-                transform_adapter.read(&camera_ref)
-            else {
-                continue;
-            };
-            let mut cameras = camera_set.p0();
-            let Ok(controller) = cameras.get_mut(*camera) else {
-                *complete = true;
-                continue;
-            };
-            let progress_t =
-                (start.elapsed().as_secs_f32() / animation_duration.as_secs_f32()).clamp(0.0, 1.0);
-            let progress = animation_curve.ease(progress_t);
-
-            let anchor_view_space = controller.anchor_view_space().unwrap_or(DVec3::new(
-                0.0,
-                0.0,
-                controller.last_anchor_depth(),
-            ));
-
-            let anchor_world = {
-                let (r, t) = (camera_rotation, camera_translation);
-                r * anchor_view_space + t
-            };
-
-            let rot_init = look_to(*initial_facing_direction, *initial_up_direction);
-            let rot_target = look_to(*target_facing_direction, *target_up_direction);
-
-            let rot_next = rot_init.slerp(rot_target, progress as f64);
-            let rot_last = camera_rotation;
-            let rot_delta = rot_next * rot_last.inverse();
-
-            let original_translation = camera_translation;
-            let original_rotation = camera_rotation;
-            rotate_around(
-                (&mut camera_translation, &mut camera_rotation),
-                anchor_world,
-                rot_delta,
-            );
-            let (_, delta_rotation, delta_translation) = {
-                let original =
-                    DAffine3::from_rotation_translation(original_rotation, original_translation);
-                let new = DAffine3::from_rotation_translation(camera_rotation, camera_translation);
-                (original.inverse() * new).to_scale_rotation_translation()
-            };
-
-            let mut camera_muts = camera_set.p2();
-            let mut camera_mut = camera_muts.get_mut(*camera).unwrap();
-            transform_adapter.apply_delta(&mut camera_mut, delta_translation, delta_rotation);
-            if progress_t >= 1.0 {
-                *complete = true;
-            }
-            redraw.write(RequestRedraw);
-        }
-        state.map.retain(|_, v| !v.complete);
-    }
+    /// The [`CameraTween::label`] of "look to" animations.
+    pub const TWEEN_LABEL: &'static str = "look_to";
 }
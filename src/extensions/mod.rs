@@ -1,9 +1,21 @@
 //! Extensions to the base camera controller.
 
+pub mod clip_planes;
+pub mod collision;
 pub mod dolly_zoom;
+pub mod follow;
+pub mod frame;
 pub mod look_to;
+pub mod turntable;
+pub mod tween;
 
 #[cfg(feature = "extension_anchor_indicator")]
 pub mod anchor_indicator;
+#[cfg(feature = "extension_bookmarks")]
+pub mod bookmarks;
+#[cfg(feature = "extension_flythrough")]
+pub mod flythrough;
+#[cfg(feature = "extension_history")]
+pub mod history;
 #[cfg(feature = "extension_independent_skybox")]
 pub mod independent_skybox;
@@ -0,0 +1,214 @@
+//! A `bevy_editor_cam` extension that slowly orbits the camera after it has been idle for some
+//! time, like a turntable. This is useful for kiosks and model reviews, to present a model when
+//! nobody is using the camera.
+//!
+//! To use it, add a [`Turntable`] component to a camera, and add the [`TurntablePlugin`] to your
+//! app. As soon as the user starts moving the camera, the turntable stops and the user continues
+//! from wherever the turntable left the camera. The turntable also stops while the camera is being
+//! animated by a [tween](super::tween) or a flythrough.
+//!
+//! Note that the idle timer only advances when the app is updating. If you are using a reactive
+//! update mode, like `WinitSettings::desktop_app()`, the app will need to be woken up for the
+//! turntable to start.
+
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DAffine3, DQuat, DVec3};
+use bevy_reflect::prelude::*;
+use bevy_time::prelude::*;
+use bevy_window::RequestRedraw;
+
+use super::tween::Tweens;
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct TurntablePlugin;
+
+impl Plugin for TurntablePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            Turntable::update
+                .after(crate::controller::component::EditorCam::update_camera_positions)
+                .in_set(crate::SyncCameraPosition),
+        );
+    }
+}
+
+/// The point a [`Turntable`] rotates the camera about.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum TurntablePivot {
+    /// Rotate about the camera's anchor, at [`EditorCam::last_anchor_depth`] in front of the
+    /// camera.
+    Anchor,
+    /// Rotate about a fixed point, in the same space as the translation read by the
+    /// [`TransformAdapter`].
+    Point(DVec3),
+}
+
+/// Optional. Slowly orbits an [`EditorCam`] after it has been idle for
+/// [`Turntable::idle_delay`].
+///
+/// The camera rotates about the up direction of the [`OrbitConstraint`]. If the constraint is
+/// [`OrbitConstraint::Free`], the camera's current up direction is used instead.
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct Turntable {
+    /// Should the turntable rotate this camera when it is idle?
+    pub enabled: bool,
+    /// How long the camera must be idle before the turntable starts.
+    pub idle_delay: Duration,
+    /// The rotation speed of the turntable, in radians per second. Positive values rotate the
+    /// camera counterclockwise about the up direction, when seen from above.
+    pub yaw_speed: f64,
+    /// How long the turntable takes to reach full speed once it starts.
+    pub ramp_up: Duration,
+    /// The point the turntable rotates about.
+    pub pivot: TurntablePivot,
+    /// Cubic curve used to ease the turntable between stopped and full speed.
+    #[reflect(ignore)]
+    pub ramp_curve: CubicSegment<Vec2>,
+    idle_time: Duration,
+    /// The progress of the speed ramp, from `0.0` when stopped to `1.0` at full speed.
+    ramp: f32,
+}
+
+impl Default for Turntable {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_delay: Duration::from_secs(10),
+            yaw_speed: 10f64.to_radians(),
+            ramp_up: Duration::from_secs(2),
+            pivot: TurntablePivot::Anchor,
+            ramp_curve: CubicSegment::new_bezier_easing((0.42, 0.0), (1.0, 1.0)),
+            idle_time: Duration::ZERO,
+            ramp: 0.0,
+        }
+    }
+}
+
+impl Turntable {
+    /// Create a turntable that starts after `idle_delay`, rotating at `yaw_speed` radians per
+    /// second about the anchor.
+    pub fn new(idle_delay: Duration, yaw_speed: f64) -> Self {
+        Self {
+            idle_delay,
+            yaw_speed,
+            ..Default::default()
+        }
+    }
+
+    /// Rotate about a fixed point instead of the anchor.
+    pub fn with_pivot(self, pivot: DVec3) -> Self {
+        Self {
+            pivot: TurntablePivot::Point(pivot),
+            ..self
+        }
+    }
+
+    /// Returns `true` if the turntable is currently rotating the camera.
+    pub fn is_rotating(&self) -> bool {
+        self.enabled && self.ramp > 0.0
+    }
+
+    /// Restart the idle timer, and stop the turntable immediately if it is rotating.
+    pub fn reset(&mut self) {
+        self.idle_time = Duration::ZERO;
+        self.ramp = 0.0;
+    }
+
+    fn update(
+        mut cameras: Query<EntityMut, (With<EditorCam>, With<Turntable>)>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        tweens: Option<Res<Tweens>>,
+        transform_adapter: Res<TransformAdapter>,
+        time: Res<Time>,
+    ) {
+        for mut camera_mut in &mut cameras {
+            // Animations need the camera to end exactly where they put it.
+            let is_animating = tweens
+                .as_ref()
+                .is_some_and(|tweens| tweens.is_animating(camera_mut.id()));
+            #[cfg(feature = "extension_flythrough")]
+            let is_animating = is_animating
+                || camera_mut
+                    .get::<super::flythrough::FlythroughPlayer>()
+                    .is_some_and(|player| {
+                        player.state() == super::flythrough::PlaybackState::Playing
+                    });
+            let Some(editor_cam) = camera_mut.get::<EditorCam>() else {
+                continue;
+            };
+            let is_moving = editor_cam.current_motion.is_moving();
+            let anchor_depth = editor_cam.last_anchor_depth();
+            let up = match editor_cam.orbit_constraint {
+                OrbitConstraint::Fixed { up, .. } => Some(up),
+                OrbitConstraint::Free => None,
+            };
+
+            let Some(mut turntable) = camera_mut.get_mut::<Turntable>() else {
+                continue;
+            };
+            // Any camera motion, including momentum, hands control back to the user immediately,
+            // so the turntable never rotates the camera away from an anchor the user picked. The
+            // turntable moves the camera transform directly, so there is nothing to undo.
+            if !turntable.enabled || is_animating || is_moving {
+                turntable.reset();
+                continue;
+            }
+            turntable.idle_time += time.delta();
+            if turntable.idle_time > turntable.idle_delay {
+                let ramp_delta = if turntable.ramp_up.is_zero() {
+                    1.0
+                } else {
+                    time.delta_secs() / turntable.ramp_up.as_secs_f32()
+                };
+                turntable.ramp = (turntable.ramp + ramp_delta).min(1.0);
+            }
+            if !turntable.is_rotating() {
+                continue;
+            }
+            let ramp_t = turntable.ramp;
+            let speed = turntable.yaw_speed * turntable.ramp_curve.ease(ramp_t) as f64;
+            let angle = speed * time.delta_secs_f64();
+            let pivot = turntable.pivot;
+            if angle == 0.0 || !angle.is_finite() {
+                continue;
+            }
+
+            let Some((camera_translation, camera_rotation)) =
+                transform_adapter.read(&camera_mut.as_readonly())
+            else {
+                continue;
+            };
+            let up = up.unwrap_or(camera_rotation * DVec3::Y).normalize_or_zero();
+            if up == DVec3::ZERO {
+                continue;
+            }
+            let pivot = match pivot {
+                TurntablePivot::Anchor => {
+                    camera_rotation * DVec3::new(0.0, 0.0, anchor_depth) + camera_translation
+                }
+                TurntablePivot::Point(point) => point,
+            };
+
+            let mut translation = camera_translation;
+            let mut rotation = camera_rotation;
+            rotate_around(
+                (&mut translation, &mut rotation),
+                pivot,
+                DQuat::from_axis_angle(up, angle),
+            );
+            let (_, delta_rotation, delta_translation) = {
+                let original =
+                    DAffine3::from_rotation_translation(camera_rotation, camera_translation);
+                let new = DAffine3::from_rotation_translation(rotation, translation);
+                (original.inverse() * new).to_scale_rotation_translation()
+            };
+            transform_adapter.apply_delta(&mut camera_mut, delta_translation, delta_rotation);
+            redraw.write(RequestRedraw);
+        }
+    }
+}
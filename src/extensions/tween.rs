@@ -0,0 +1,413 @@
+//! A `bevy_editor_cam` extension that animates cameras along easing curves. This is the machinery
+//! used by the other animated extensions, like [`LookTo`](super::look_to::LookTo) and
+//! [`DollyZoom`](super::dolly_zoom::DollyZoom), and can be used to build new transitions.
+//!
+//! A [`CameraTween`] can animate any combination of rotation, translation, field of view, and
+//! orthographic scale. Start one with [`Tweens::start`], and listen for [`TweenComplete`] to find
+//! out when it has finished:
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use bevy::math::{DQuat, DVec3};
+//! # use bevy_editor_cam::prelude::*;
+//! # use bevy_editor_cam::extensions::tween::*;
+//! fn spin(mut cameras: Query<(Entity, &mut EditorCam)>, mut tweens: ResMut<Tweens>) {
+//!     for (entity, mut editor_cam) in &mut cameras {
+//!         let tween = CameraTween {
+//!             rotation: Some(TweenRotation {
+//!                 from: DQuat::IDENTITY,
+//!                 to: DQuat::from_rotation_y(std::f64::consts::PI),
+//!                 pivot: RotationPivot::Anchor,
+//!             }),
+//!             ..CameraTween::new("spin")
+//!         };
+//!         tweens.start(entity, &mut editor_cam, tween);
+//!     }
+//! }
+//!
+//! fn spin_finished(mut complete: MessageReader<TweenComplete>) {
+//!     for event in complete.read().filter(|event| event.label == "spin") {
+//!         info!("Camera {} finished spinning", event.camera);
+//!     }
+//! }
+//! ```
+
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_camera::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::{prelude::*, DAffine3, DQuat, DVec3};
+use bevy_platform::{collections::HashMap, time::Instant};
+use bevy_window::RequestRedraw;
+
+use crate::prelude::*;
+
+/// See the [module](self) docs.
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Tweens>()
+            .add_message::<TweenComplete>()
+            .add_systems(
+                PreUpdate,
+                Tweens::update
+                    .before(crate::controller::component::EditorCam::update_camera_positions)
+                    .in_set(crate::SyncCameraPosition),
+            );
+    }
+}
+
+/// Sent when a [`CameraTween`] has finished animating a camera.
+#[derive(Debug, Clone, Message)]
+pub struct TweenComplete {
+    /// The camera that was animated.
+    pub camera: Entity,
+    /// The [`CameraTween::label`] of the tween that finished.
+    pub label: &'static str,
+    /// `true` if the tween was replaced by another tween with the same label before it finished.
+    pub interrupted: bool,
+}
+
+/// The point a [`TweenRotation`] rotates the camera about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RotationPivot {
+    /// Rotate about the camera's anchor, like an orbit.
+    Anchor,
+    /// Rotate the camera in place.
+    Camera,
+}
+
+/// Rotates the camera over the course of a [`CameraTween`].
+#[derive(Debug, Clone, Copy)]
+pub struct TweenRotation {
+    /// The rotation at the start of the tween.
+    pub from: DQuat,
+    /// The rotation at the end of the tween. When `from` is the camera's rotation at the start of
+    /// the tween, the camera will end with this rotation.
+    pub to: DQuat,
+    /// The point the camera rotates about.
+    pub pivot: RotationPivot,
+}
+
+/// Animates the field of view of a perspective camera over the course of a [`CameraTween`].
+#[derive(Debug, Clone, Copy)]
+pub struct TweenFov {
+    /// The vertical field of view at the start of the tween, in radians.
+    pub from: f64,
+    /// The vertical field of view at the end of the tween, in radians.
+    pub to: f64,
+    /// If set, the camera is moved along its view axis to keep this half height of the view
+    /// constant at the anchor, making this a dolly zoom. This is `tan(fov / 2) * anchor_depth`.
+    pub dolly: Option<f64>,
+}
+
+/// An animation of a camera, started with [`Tweens::start`].
+///
+/// All changes are applied incrementally each frame, so tweens with different labels can animate
+/// the same camera at the same time, as long as they animate different properties.
+#[derive(Debug, Clone)]
+pub struct CameraTween {
+    /// Identifies this kind of tween. Starting a tween replaces any tween with the same label that
+    /// is animating the same camera. This is also sent with [`TweenComplete`].
+    pub label: &'static str,
+    /// The duration of the animation.
+    pub duration: Duration,
+    /// The cubic curve used to ease the animation.
+    pub curve: CubicSegment<Vec2>,
+    /// Rotate the camera.
+    pub rotation: Option<TweenRotation>,
+    /// Move the camera by this total translation, in the same space as the [`TransformAdapter`].
+    pub translation: Option<DVec3>,
+    /// Animate the field of view of a perspective camera.
+    pub fov: Option<TweenFov>,
+    /// Animate the scale of an orthographic camera, from the first value to the second value.
+    pub ortho_scale: Option<(f32, f32)>,
+    /// Keep the anchor depth of the camera at the depth of this point, in the same space as the
+    /// [`TransformAdapter`].
+    pub focus: Option<DVec3>,
+    /// Replace the camera's projection with this one when the tween finishes. If this is an
+    /// orthographic projection and the tween is a dolly zoom, the scale is set to match the
+    /// perspective view at the anchor.
+    pub end_projection: Option<Projection>,
+    /// Move the camera to exactly this translation when the tween finishes, in the same space as
+    /// the [`TransformAdapter`]. Other systems can move the camera during the tween, like the
+    /// orthographic projection keeping the anchor in front of the camera, so use this when the
+    /// camera must end at a known position.
+    pub end_translation: Option<DVec3>,
+    /// Set the [`EditorCam::last_anchor_depth`] of the camera to this depth when the tween
+    /// finishes.
+    pub end_anchor_depth: Option<f64>,
+    /// Disable user control of the camera until the tween finishes.
+    pub disable_motion: bool,
+}
+
+impl CameraTween {
+    /// A tween that does nothing over the default duration, to be filled in with the properties to
+    /// animate.
+    pub fn new(label: &'static str) -> Self {
+        Self {
+            label,
+            duration: Duration::from_millis(400),
+            curve: CubicSegment::new_bezier_easing((0.25, 0.0), (0.25, 1.0)),
+            rotation: None,
+            translation: None,
+            fov: None,
+            ortho_scale: None,
+            focus: None,
+            end_projection: None,
+            end_translation: None,
+            end_anchor_depth: None,
+            disable_motion: false,
+        }
+    }
+
+    /// Set the duration and easing curve of this tween.
+    pub fn with_animation(self, duration: Duration, curve: CubicSegment<Vec2>) -> Self {
+        Self {
+            duration,
+            curve,
+            ..self
+        }
+    }
+}
+
+struct ActiveTween {
+    tween: CameraTween,
+    start: Instant,
+    last_progress: f32,
+    initial_enabled: Option<EnabledMotion>,
+    complete: bool,
+}
+
+/// Stores the state of all running [`CameraTween`]s.
+#[derive(Resource, Default)]
+pub struct Tweens {
+    map: HashMap<Entity, Vec<ActiveTween>>,
+    interrupted: Vec<(Entity, &'static str)>,
+}
+
+impl Tweens {
+    /// Start animating `camera`. This ends any current motion of the camera, and replaces any
+    /// running tween with the same label.
+    pub fn start(&mut self, camera: Entity, editor_cam: &mut EditorCam, tween: CameraTween) {
+        let tweens = self.map.entry(camera).or_default();
+        let mut initial_enabled = None;
+        if let Some(index) = tweens.iter().position(|t| t.tween.label == tween.label) {
+            let replaced = tweens.remove(index);
+            initial_enabled = replaced.initial_enabled;
+            self.interrupted.push((camera, tween.label));
+        }
+        if tween.disable_motion {
+            // Other tweens may have already disabled motion, restore it when they finish instead.
+            if tweens.iter().all(|t| t.initial_enabled.is_none()) {
+                initial_enabled.get_or_insert_with(|| editor_cam.enabled_motion.clone());
+            }
+            editor_cam.enabled_motion = EnabledMotion {
+                pan: false,
+                orbit: false,
+                zoom: false,
+            };
+        } else if let Some(enabled) = initial_enabled.take() {
+            if let Some(other) = tweens.iter_mut().find(|t| t.tween.disable_motion) {
+                other.initial_enabled = Some(enabled);
+            } else {
+                editor_cam.enabled_motion = enabled;
+            }
+        }
+
+        editor_cam.end_move();
+        editor_cam.current_motion = motion::CurrentMotion::Stationary;
+        tweens.push(ActiveTween {
+            tween,
+            start: Instant::now(),
+            last_progress: 0.0,
+            initial_enabled,
+            complete: false,
+        });
+    }
+
+    /// Returns `true` if a tween with this label is animating the camera.
+    pub fn is_running(&self, camera: Entity, label: &'static str) -> bool {
+        self.map
+            .get(&camera)
+            .is_some_and(|tweens| tweens.iter().any(|t| t.tween.label == label))
+    }
+
+    /// Returns `true` if any tween is animating the camera.
+    pub fn is_animating(&self, camera: Entity) -> bool {
+        self.map
+            .get(&camera)
+            .is_some_and(|tweens| !tweens.is_empty())
+    }
+
+    pub(crate) fn update(
+        mut state: ResMut<Self>,
+        mut camera_set: ParamSet<(
+            Query<(&Camera, &mut Projection, &mut EditorCam)>,
+            Query<EntityMut, With<EditorCam>>,
+        )>,
+        mut redraw: MessageWriter<RequestRedraw>,
+        mut complete_events: MessageWriter<TweenComplete>,
+        transform_adapter: Res<TransformAdapter>,
+    ) {
+        let state = &mut *state;
+        for (camera, label) in state.interrupted.drain(..) {
+            complete_events.write(TweenComplete {
+                camera,
+                label,
+                interrupted: true,
+            });
+        }
+
+        for (camera_entity, tweens) in state.map.iter_mut() {
+            for active in tweens.iter_mut() {
+                let mut camera_muts = camera_set.p1();
+                let Ok(camera_mut) = camera_muts.get_mut(*camera_entity) else {
+                    active.complete = true;
+                    continue;
+                };
+                let Some((camera_translation, camera_rotation)) =
+                    transform_adapter.read(&camera_mut.as_readonly())
+                else {
+                    active.complete = true;
+                    continue;
+                };
+
+                let mut cameras = camera_set.p0();
+                let Ok((camera, mut projection, mut controller)) = cameras.get_mut(*camera_entity)
+                else {
+                    active.complete = true;
+                    continue;
+                };
+                let tween = &active.tween;
+                let progress_t = (active.start.elapsed().as_secs_f32()
+                    / tween.duration.as_secs_f32())
+                .clamp(0.0, 1.0);
+                let finished = progress_t >= 1.0 || progress_t.is_nan();
+                let progress = if finished {
+                    1.0
+                } else {
+                    tween.curve.ease(progress_t)
+                };
+                let last_progress = active.last_progress;
+                active.last_progress = progress;
+
+                let mut translation = camera_translation;
+                let mut rotation = camera_rotation;
+
+                if let Some(TweenRotation { from, to, pivot }) = tween.rotation {
+                    let rot_last = from.slerp(to, last_progress as f64);
+                    let rot_next = from.slerp(to, progress as f64);
+                    let rot_delta = rot_next * rot_last.inverse();
+                    let pivot = match pivot {
+                        RotationPivot::Anchor => {
+                            let anchor_view_space = controller
+                                .anchor_view_space()
+                                .unwrap_or(DVec3::new(0.0, 0.0, controller.last_anchor_depth()));
+                            rotation * anchor_view_space + translation
+                        }
+                        RotationPivot::Camera => translation,
+                    };
+                    rotate_around((&mut translation, &mut rotation), pivot, rot_delta);
+                }
+
+                if let Some(total) = tween.translation {
+                    translation += total * (progress - last_progress) as f64;
+                }
+
+                if let Some(TweenFov { from, to, dolly }) = tween.fov {
+                    let next_fov = from + (to - from) * progress as f64;
+                    if let Projection::Perspective(perspective) = &mut *projection {
+                        if let Some(triangle_base) = dolly {
+                            let last_dist = triangle_base / (perspective.fov as f64 / 2.0).tan();
+                            let next_dist = triangle_base / (next_fov / 2.0).tan();
+                            let forward_dist = last_dist - next_dist;
+                            translation += cam_forward(rotation) * forward_dist;
+                            controller.last_anchor_depth += forward_dist;
+                        }
+                        perspective.fov = next_fov as f32;
+                    }
+                }
+
+                if let (Some((from, to)), Projection::Orthographic(ortho)) =
+                    (tween.ortho_scale, &mut *projection)
+                {
+                    ortho.scale = from.lerp(to, progress);
+                }
+
+                if let Some(focus) = tween.focus {
+                    let depth = (focus - translation).dot(cam_forward(rotation));
+                    controller.last_anchor_depth = -depth.abs();
+                }
+
+                if finished {
+                    if let Some(end_translation) = tween.end_translation {
+                        translation = end_translation;
+                    }
+                    if let Some(depth) = tween.end_anchor_depth {
+                        controller.last_anchor_depth = -depth.abs();
+                    }
+                    if let Some(end_projection) = &tween.end_projection {
+                        *projection = end_projection.clone();
+                        if let (
+                            Some(TweenFov {
+                                dolly: Some(base), ..
+                            }),
+                            Projection::Orthographic(ortho),
+                        ) = (tween.fov, &mut *projection)
+                        {
+                            let multiplier =
+                                crate::controller::zoom::ortho_tri_base_to_scale_factor(
+                                    camera, ortho,
+                                );
+                            ortho.scale = (base * multiplier) as f32;
+                        }
+                    }
+                    active.complete = true;
+                    complete_events.write(TweenComplete {
+                        camera: *camera_entity,
+                        label: tween.label,
+                        interrupted: false,
+                    });
+                }
+                redraw.write(RequestRedraw);
+
+                let (_, delta_rotation, delta_translation) = {
+                    let original =
+                        DAffine3::from_rotation_translation(camera_rotation, camera_translation);
+                    let new = DAffine3::from_rotation_translation(rotation, translation);
+                    (original.inverse() * new).to_scale_rotation_translation()
+                };
+                if delta_translation != DVec3::ZERO || delta_rotation != DQuat::IDENTITY {
+                    let mut camera_muts = camera_set.p1();
+                    let Ok(mut camera_mut) = camera_muts.get_mut(*camera_entity) else {
+                        active.complete = true;
+                        continue;
+                    };
+                    transform_adapter.apply_delta(
+                        &mut camera_mut,
+                        delta_translation,
+                        delta_rotation,
+                    );
+                }
+            }
+
+            let restore = tweens
+                .iter_mut()
+                .filter(|t| t.complete)
+                .find_map(|t| t.initial_enabled.take());
+            tweens.retain(|t| !t.complete);
+            if let Some(enabled) = restore {
+                // Motion stays disabled while any other tween needs it to be.
+                if let Some(next) = tweens.iter_mut().find(|t| t.tween.disable_motion) {
+                    next.initial_enabled = Some(enabled);
+                } else if let Ok((_, _, mut controller)) = camera_set.p0().get_mut(*camera_entity) {
+                    controller.enabled_motion = enabled;
+                }
+            }
+        }
+        state.map.retain(|_, tweens| !tweens.is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 //! Provides a default input plugin for the camera. See [`DefaultInputPlugin`].
 
 use bevy_app::prelude::*;
-use bevy_camera::{prelude::*, RenderTarget};
+use bevy_camera::{prelude::*, primitives::Aabb, RenderTarget};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::prelude::*;
 use bevy_input::{
@@ -11,6 +11,7 @@ use bevy_input::{
 use bevy_math::{prelude::*, DVec2, DVec3};
 use bevy_platform::collections::HashMap;
 use bevy_reflect::prelude::*;
+use bevy_time::prelude::*;
 use bevy_transform::prelude::*;
 use bevy_window::{PrimaryWindow, Window};
 
@@ -18,7 +19,13 @@ use bevy_picking::pointer::{
     PointerAction, PointerId, PointerInput, PointerInteraction, PointerLocation, PointerMap,
 };
 
-use crate::prelude::{component::EditorCam, inputs::MotionInputs};
+use crate::prelude::{component::EditorCam, inputs::MotionInputs, smoothing::SmoothingFilter};
+
+pub mod anchor;
+pub mod scroll;
+
+use anchor::{AnchorBounds, AnchorSelection, AnchorSettings};
+use scroll::ScrollZoom;
 
 /// The type of mutually exclusive camera motion.
 #[derive(Debug, Clone, Copy, Reflect, PartialEq, Eq)]
@@ -55,6 +62,8 @@ impl Plugin for DefaultInputPlugin {
                 PreUpdate,
                 (
                     default_camera_inputs,
+                    AnchorSettings::cast_center_rays,
+                    AnchorSettings::filter_hits,
                     EditorCamInputMessage::receive_messages,
                     EditorCamInputMessage::send_pointer_inputs,
                 )
@@ -72,7 +81,13 @@ pub fn default_camera_inputs(
     mut controller: MessageWriter<EditorCamInputMessage>,
     mut mouse_wheel: MessageReader<MouseWheel>,
     mouse_input: Res<ButtonInput<MouseButton>>,
-    cameras: Query<(Entity, &Camera, &RenderTarget, &EditorCam)>,
+    cameras: Query<(
+        Entity,
+        &Camera,
+        &RenderTarget,
+        &EditorCam,
+        Option<&ScrollZoom>,
+    )>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
 ) {
     let orbit_start = MouseButton::Right;
@@ -82,17 +97,30 @@ pub fn default_camera_inputs(
     if let Some(&camera) = pointer_map.get(&PointerId::Mouse) {
         let camera_query = cameras.get(camera).ok();
         let is_in_zoom_mode = camera_query
-            .map(|(.., editor_cam)| editor_cam.current_motion.is_zooming_only())
+            .map(|(.., editor_cam, _)| editor_cam.current_motion.is_zooming_only())
             .unwrap_or_default();
         let zoom_amount_abs = camera_query
-            .and_then(|(.., editor_cam)| {
+            .and_then(|(.., editor_cam, _)| {
+                let SmoothingFilter::MovingAverage(window) = editor_cam.smoothing.zoom else {
+                    return None;
+                };
                 editor_cam
                     .current_motion
                     .inputs()
-                    .map(|inputs| inputs.zoom_velocity_abs(editor_cam.smoothing.zoom.mul_f32(2.0)))
+                    .map(|inputs| inputs.zoom_velocity_abs(window.mul_f32(2.0)))
             })
             .unwrap_or(0.0);
-        let should_zoom_end = is_in_zoom_mode && zoom_amount_abs <= zoom_stop;
+        // Filters without a window have consumed all inputs once they have settled.
+        let is_zoom_settled = camera_query
+            .and_then(|(.., editor_cam, _)| editor_cam.current_motion.inputs())
+            .map(|inputs| inputs.zoom_inputs().is_settled())
+            .unwrap_or(true);
+        // Stepped zoom sends inputs until each step has finished easing.
+        let is_stepping = camera_query
+            .and_then(|(.., scroll_zoom)| scroll_zoom)
+            .is_some_and(ScrollZoom::is_stepping);
+        let should_zoom_end =
+            is_in_zoom_mode && zoom_amount_abs <= zoom_stop && is_zoom_settled && !is_stepping;
 
         if mouse_input.any_just_released([orbit_start, pan_start]) || should_zoom_end {
             controller.write(EditorCamInputMessage::End { camera });
@@ -105,7 +133,7 @@ pub fn default_camera_inputs(
     {
         match pointer {
             PointerId::Mouse => {
-                let Some((camera, ..)) = cameras.iter().find(|(_, camera, render_target, _)| {
+                let Some((camera, ..)) = cameras.iter().find(|(_, camera, render_target, ..)| {
                     pointer_location.is_in_viewport(camera, render_target, &primary_window)
                 }) else {
                     continue; // Pointer must be in viewport to start a motion.
@@ -193,6 +221,9 @@ impl EditorCamInputMessage {
         pointer_locations: Query<&PointerLocation>,
         cameras: Query<(&Camera, &Projection)>,
         windows: Query<&Window>,
+        anchor_settings: Query<&AnchorSettings>,
+        selection: Query<&GlobalTransform, With<AnchorSelection>>,
+        bounds: Query<(&GlobalTransform, &Aabb), With<AnchorBounds>>,
     ) {
         for event in events.read() {
             let Ok((mut controller, cam_transform)) = controllers.get_mut(event.camera()) else {
@@ -204,51 +235,57 @@ impl EditorCamInputMessage {
                     if controller.is_actively_controlled() {
                         continue;
                     }
-                    let anchor = pointer_map
-                        .get_entity(*pointer)
-                        .and_then(|entity| pointer_interactions.get(entity).ok())
-                        .and_then(|interaction| interaction.get_nearest_hit())
-                        // Since `bevy` 0.17.3:
-                        //
-                        // If the current hit is on a window, we cannot use the `hit.position` as an anchor
-                        // as the `hit.position` is in viewport coordinates.
-                        .filter(|(entity, _hit)| !windows.contains(*entity))
-                        .and_then(|(_, hit)| hit.position)
-                        .map(|world_space_hit| {
+                    let settings = anchor_settings.get(event.camera()).ok();
+                    let world_to_view = EditorCam::view_to_world(cam_transform).inverse();
+                    let pointer_hit = match settings {
+                        // The hits have already been filtered by `AnchorSettings::filter_hits`.
+                        Some(settings) => settings.pointer_hit(),
+                        None => pointer_map
+                            .get_entity(*pointer)
+                            .and_then(|entity| pointer_interactions.get(entity).ok())
+                            .and_then(|interaction| interaction.get_nearest_hit())
+                            // Since `bevy` 0.17.3:
+                            //
+                            // If the current hit is on a window, we cannot use the `hit.position` as an anchor
+                            // as the `hit.position` is in viewport coordinates.
+                            .filter(|(entity, _hit)| !windows.contains(*entity))
+                            .and_then(|(_, hit)| hit.position)
+                            .map(|world_space_hit| world_space_hit.as_dvec3()),
+                    };
+                    let anchor = anchor::source_anchor(
+                        settings,
+                        *kind,
+                        controller.last_anchor_depth(),
+                        cam_transform,
+                        selection.iter().map(|t| t.translation().as_dvec3()),
+                    )
+                    .map(|world_space| world_to_view.transform_point3(world_space))
+                    .filter(|p| p.is_finite())
+                    .or_else(|| {
+                        pointer_hit
                             // Convert the world space hit to view (camera) space
-                            cam_transform
-                                .to_matrix()
-                                .as_dmat4()
-                                .inverse()
-                                .transform_point3(world_space_hit.into())
-                        })
-                        .filter(|p| {
-                            #[cfg(debug_assertions)]
-                            if !p.is_finite() {
-                                bevy_log::warn!("Non-finite input fed to camera controller: {p:?}")
-                            }
-                            p.is_finite()
-                        })
-                        .or_else(|| {
-                            let camera = cameras.get(event.camera()).ok();
-                            let pointer_location = pointer_map
-                                .get_entity(*pointer)
-                                .and_then(|entity| pointer_locations.get(entity).ok())
-                                .and_then(|l| l.location());
-                            if let Some(((camera, proj), pointer_location)) =
-                                camera.zip(pointer_location)
-                            {
-                                screen_to_view_space(
-                                    camera,
-                                    proj,
-                                    &controller,
-                                    pointer_location.position,
-                                )
-                            } else {
-                                None
-                            }
-                        })
-                        .filter(|p| p.is_finite());
+                            .map(|world_space_hit| world_to_view.transform_point3(world_space_hit))
+                            .filter(|p| {
+                                #[cfg(debug_assertions)]
+                                if !p.is_finite() {
+                                    bevy_log::warn!(
+                                        "Non-finite input fed to camera controller: {p:?}"
+                                    )
+                                }
+                                p.is_finite()
+                            })
+                    })
+                    .or_else(|| {
+                        let (camera, proj) = cameras.get(event.camera()).ok()?;
+                        let pointer_location = pointer_map
+                            .get_entity(*pointer)
+                            .and_then(|entity| pointer_locations.get(entity).ok())
+                            .and_then(|l| l.location())?;
+                        let ray = screen_to_view_ray(camera, proj, pointer_location.position)?;
+                        anchor::fallback_anchor(settings, ray, cam_transform, &bounds)
+                            .or_else(|| Some(screen_to_view_space(proj, &controller, ray)))
+                    })
+                    .filter(|p| p.is_finite());
 
                     match kind {
                         MotionKind::OrbitZoom => controller.start_orbit(anchor),
@@ -284,13 +321,21 @@ impl EditorCamInputMessage {
     /// movement information.
     pub fn send_pointer_inputs(
         camera_map: Res<CameraPointerMap>,
-        mut camera_controllers: Query<&mut EditorCam>,
+        mut camera_controllers: Query<(
+            &mut EditorCam,
+            &Camera,
+            &Projection,
+            Option<&mut ScrollZoom>,
+        )>,
         mut mouse_wheel: MessageReader<MouseWheel>,
         mut moves: MessageReader<PointerInput>,
+        time: Res<Time>,
     ) {
         let moves_list: Vec<_> = moves.read().collect();
         for (pointer, camera) in camera_map.iter() {
-            let Ok(mut camera_controller) = camera_controllers.get_mut(*camera) else {
+            let Ok((mut camera_controller, camera, projection, scroll_zoom)) =
+                camera_controllers.get_mut(*camera)
+            else {
                 continue;
             };
 
@@ -305,23 +350,39 @@ impl EditorCamInputMessage {
                 })
                 .sum();
 
-            let zoom_amount = match pointer {
+            let (zoom_amount, is_stepped) = match pointer {
                 // TODO: add pinch zoom support
-                PointerId::Mouse => mouse_wheel
-                    .read()
-                    .map(|mw| {
-                        let scroll_multiplier = match mw.unit {
-                            MouseScrollUnit::Line => 150.0,
-                            MouseScrollUnit::Pixel => 1.0,
-                        };
-                        mw.y * scroll_multiplier
-                    })
-                    .sum::<f32>(),
-                _ => 0.0,
+                PointerId::Mouse => {
+                    let pixels_per_line = scroll_zoom
+                        .as_ref()
+                        .map_or(ScrollZoom::DEFAULT_PIXELS_PER_LINE, |s| s.pixels_per_line);
+                    let lines = mouse_wheel
+                        .read()
+                        .map(|mw| match mw.unit {
+                            MouseScrollUnit::Line => mw.y,
+                            MouseScrollUnit::Pixel => mw.y / pixels_per_line,
+                        })
+                        .sum::<f32>();
+                    match scroll_zoom {
+                        Some(mut scroll_zoom) if lines != 0.0 || scroll_zoom.is_stepping() => {
+                            let fraction_per_input =
+                                camera_controller.zoom_fraction_per_input(camera, projection);
+                            scroll_zoom.zoom_input(lines, time.delta(), fraction_per_input)
+                        }
+                        _ => (lines * pixels_per_line, false),
+                    }
+                }
+                _ => (0.0, false),
             };
 
             camera_controller.send_screenspace_input(screenspace_input);
-            camera_controller.send_zoom_input(zoom_amount);
+            if is_stepped {
+                // Steps are already eased, smoothing them again would delay them past the step
+                // duration, and add momentum.
+                camera_controller.send_unsmoothed_zoom_input(zoom_amount);
+            } else {
+                camera_controller.send_zoom_input(zoom_amount);
+            }
         }
         // This must be cleared manually because reading these inputs is conditional - we are not
         // guaranteed to be flushing the events every frame.
@@ -329,12 +390,13 @@ impl EditorCamInputMessage {
     }
 }
 
-fn screen_to_view_space(
+/// The ray through a point on the screen, in view space, as an origin on the near plane and a
+/// normalized direction.
+fn screen_to_view_ray(
     camera: &Camera,
     proj: &Projection,
-    controller: &EditorCam,
     target_position: Vec2,
-) -> Option<DVec3> {
+) -> Option<(DVec3, DVec3)> {
     let mut viewport_position = if let Some(rect) = camera.logical_viewport_rect() {
         target_position.as_dvec2() - rect.min.as_dvec2()
     } else {
@@ -351,12 +413,24 @@ fn screen_to_view_space(
             // Using EPSILON because an NDC with Z = 0 returns NaNs.
             let view_far_plane = ndc_to_view.project_point3(ndc.extend(f64::EPSILON));
             let direction = (view_far_plane - view_near_plane).normalize();
-            Some((direction / direction.z) * controller.last_anchor_depth())
+            Some((view_near_plane, direction))
+        }
+        Projection::Orthographic(_) => Some((view_near_plane, DVec3::NEG_Z)),
+    }
+}
+
+/// The point along a view space ray at the last anchor depth of the camera.
+fn screen_to_view_space(
+    proj: &Projection,
+    controller: &EditorCam,
+    (origin, direction): (DVec3, DVec3),
+) -> DVec3 {
+    match &proj {
+        Projection::Perspective(_) | Projection::Custom(_) => {
+            (direction / direction.z) * controller.last_anchor_depth()
+        }
+        Projection::Orthographic(_) => {
+            DVec3::new(origin.x, origin.y, controller.last_anchor_depth())
         }
-        Projection::Orthographic(_) => Some(DVec3::new(
-            view_near_plane.x,
-            view_near_plane.y,
-            controller.last_anchor_depth(),
-        )),
     }
 }
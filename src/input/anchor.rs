@@ -0,0 +1,538 @@
+//! Controls where the anchor of a camera motion is placed when the [`DefaultInputPlugin`] starts a
+//! motion. See [`AnchorSettings`].
+//!
+//! [`DefaultInputPlugin`]: super::DefaultInputPlugin
+
+use std::any::TypeId;
+
+use bevy_camera::{primitives::Aabb, visibility::RenderLayers};
+use bevy_ecs::{message::MessageCursor, prelude::*, system::SystemId};
+use bevy_log::prelude::*;
+use bevy_math::{DMat4, DVec3};
+use bevy_picking::pointer::{PointerInteraction, PointerMap};
+use bevy_reflect::prelude::*;
+use bevy_transform::prelude::*;
+use bevy_window::Window;
+
+use super::{EditorCamInputMessage, MotionKind};
+use crate::prelude::{
+    ray_cast::{CameraRay, RayCaster},
+    EditorCam,
+};
+
+/// Where the anchor of a camera motion should be placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Reflect)]
+pub enum AnchorSource {
+    /// The nearest picking hit under the pointer that started the motion.
+    #[default]
+    PointerHit,
+    /// The nearest hit at the center of the viewport. This requires
+    /// [`AnchorSettings::ray_cast`] to be set, otherwise the anchor is placed in the center of the
+    /// viewport at the last anchor depth.
+    ScreenCenter,
+    /// A fixed point in world space, like the "set rotation center" command of CAD software.
+    Pivot(DVec3),
+    /// The centroid of all entities with an [`AnchorSelection`] component. If there are none, the
+    /// pointer hit is used instead.
+    Selection,
+}
+
+/// Marks an entity as part of the selection used by [`AnchorSource::Selection`].
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+pub struct AnchorSelection;
+
+/// Marks an entity whose [`Aabb`] is used by the [`AnchorFallback::SceneSphere`] and
+/// [`AnchorFallback::NearestAabb`] fallbacks. Usually added to the important objects of a scene.
+#[derive(Debug, Clone, Copy, Default, Component, Reflect)]
+pub struct AnchorBounds;
+
+/// Places the anchor when a motion starts without anything under the pointer. Fallbacks are tried
+/// in the order listed in [`AnchorSettings::fallbacks`], and if none of them find a point in front
+/// of the camera, the anchor is placed at the last anchor depth.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+pub enum AnchorFallback {
+    /// Intersect the pointer ray with an infinite plane, like a ground plane or grid.
+    Plane {
+        /// Any point on the plane, in world space.
+        point: DVec3,
+        /// The normal of the plane, in world space.
+        normal: DVec3,
+    },
+    /// Intersect the pointer ray with the bounding sphere of all entities with an
+    /// [`AnchorBounds`] component.
+    SceneSphere,
+    /// Use the point on the bounds of the entities with an [`AnchorBounds`] component that is
+    /// nearest to the pointer ray. If the ray passes through some bounds, the nearest entry point
+    /// is used.
+    NearestAabb,
+}
+
+impl AnchorFallback {
+    /// A ground plane through the origin, facing up.
+    pub const GROUND_PLANE: Self = Self::Plane {
+        point: DVec3::ZERO,
+        normal: DVec3::Y,
+    };
+
+    /// Find the world space anchor along a world space ray.
+    fn anchor(
+        &self,
+        origin: DVec3,
+        direction: DVec3,
+        bounds: &Query<(&GlobalTransform, &Aabb), With<AnchorBounds>>,
+    ) -> Option<DVec3> {
+        match *self {
+            AnchorFallback::Plane { point, normal } => {
+                let denominator = normal.dot(direction);
+                if denominator.abs() <= f64::EPSILON {
+                    return None;
+                }
+                let t = normal.dot(point - origin) / denominator;
+                (t > 0.0).then(|| origin + direction * t)
+            }
+            AnchorFallback::SceneSphere => {
+                let (min, max) = bounds
+                    .iter()
+                    .flat_map(|(transform, aabb)| {
+                        let to_world = transform.to_matrix().as_dmat4();
+                        aabb_corners(aabb).map(move |corner| to_world.transform_point3(corner))
+                    })
+                    .fold(None, |acc: Option<(DVec3, DVec3)>, p| match acc {
+                        Some((min, max)) => Some((min.min(p), max.max(p))),
+                        None => Some((p, p)),
+                    })?;
+                let center = (min + max) / 2.0;
+                let radius = (max - min).length() / 2.0;
+                // Solve |origin + direction * t - center| = radius, for a normalized direction.
+                let offset = origin - center;
+                let b = offset.dot(direction);
+                let c = offset.length_squared() - radius * radius;
+                let discriminant = b * b - c;
+                if discriminant < 0.0 {
+                    return None;
+                }
+                let root = discriminant.sqrt();
+                // Use the exit point if the camera is inside the sphere.
+                [-b - root, -b + root]
+                    .into_iter()
+                    .find(|t| *t > 0.0)
+                    .map(|t| origin + direction * t)
+            }
+            AnchorFallback::NearestAabb => bounds
+                .iter()
+                .filter_map(|(transform, aabb)| {
+                    nearest_on_aabb(transform.to_matrix().as_dmat4(), aabb, origin, direction)
+                })
+                .min_by(|(a_miss, a_t, _), (b_miss, b_t, _)| {
+                    a_miss.total_cmp(b_miss).then(a_t.total_cmp(b_t))
+                })
+                .map(|(.., point)| point),
+        }
+    }
+}
+
+fn aabb_corners(aabb: &Aabb) -> impl Iterator<Item = DVec3> {
+    let center = aabb.center.as_dvec3();
+    let half = aabb.half_extents.as_dvec3();
+    (0..8).map(move |i| {
+        let sign = DVec3::new(
+            if i & 1 == 0 { -1.0 } else { 1.0 },
+            if i & 2 == 0 { -1.0 } else { 1.0 },
+            if i & 4 == 0 { -1.0 } else { 1.0 },
+        );
+        center + half * sign
+    })
+}
+
+/// Find the point on an entity's bounds nearest to a world space ray. Returns the distance from
+/// the ray to the point, the distance along the ray, and the world space point.
+fn nearest_on_aabb(
+    to_world: DMat4,
+    aabb: &Aabb,
+    origin: DVec3,
+    direction: DVec3,
+) -> Option<(f64, f64, DVec3)> {
+    let to_local = to_world.inverse();
+    let local_origin = to_local.transform_point3(origin);
+    let local_direction = to_local.transform_vector3(direction);
+    let center = aabb.center.as_dvec3();
+    let half = aabb.half_extents.as_dvec3();
+    let (min, max) = (center - half, center + half);
+
+    // Slab test, the distance along the ray is the same in local and world space because the
+    // direction is transformed without being normalized.
+    let mut t_near = f64::NEG_INFINITY;
+    let mut t_far = f64::INFINITY;
+    for axis in 0..3 {
+        let (o, d) = (local_origin[axis], local_direction[axis]);
+        if d == 0.0 {
+            // The ray is parallel to this slab, so it is either always or never inside of it.
+            if o < min[axis] || o > max[axis] {
+                t_far = f64::NEG_INFINITY;
+            }
+            continue;
+        }
+        let (t0, t1) = ((min[axis] - o) / d, (max[axis] - o) / d);
+        t_near = t_near.max(t0.min(t1));
+        t_far = t_far.min(t0.max(t1));
+    }
+    if t_near <= t_far && t_far > 0.0 {
+        // Use the exit point if the camera is inside the bounds.
+        let t = if t_near > 0.0 { t_near } else { t_far };
+        return Some((0.0, t, origin + direction * t)).filter(|(.., p)| p.is_finite());
+    }
+
+    // The ray misses, use the point on the bounds closest to the point on the ray nearest the
+    // center of the bounds.
+    let t =
+        ((center - local_origin).dot(local_direction) / local_direction.length_squared()).max(0.0);
+    let local_point = (local_origin + local_direction * t).clamp(min, max);
+    let point = to_world.transform_point3(local_point);
+    let miss = point.distance(origin + direction * t);
+    Some((miss, t, point)).filter(|(miss, t, p)| miss.is_finite() && t.is_finite() && p.is_finite())
+}
+
+/// Decides which pointer hits can be used as an anchor, so helper geometry like gizmos, grids,
+/// transparent volumes, and annotations does not become the center of camera motions. The nearest
+/// hit that passes all of the filters is used.
+#[derive(Debug, Clone, Default)]
+pub struct AnchorFilter {
+    /// If not empty, only entities with at least one of these components can be hit.
+    pub include: Vec<TypeId>,
+    /// Entities with any of these components are ignored.
+    pub exclude: Vec<TypeId>,
+    /// If set, only entities with [`RenderLayers`] that intersect these layers can be hit.
+    /// Entities without [`RenderLayers`] are on the default layer.
+    pub render_layers: Option<RenderLayers>,
+    /// A registered system that is run for each hit entity, and should return `true` if the entity
+    /// can be used as an anchor.
+    pub predicate: Option<SystemId<In<Entity>, bool>>,
+}
+
+impl AnchorFilter {
+    /// Only allow entities with the component `C` to be hit. Can be called multiple times to
+    /// allow entities with any of the components.
+    pub fn include<C: Component>(mut self) -> Self {
+        self.include.push(TypeId::of::<C>());
+        self
+    }
+
+    /// Ignore entities with the component `C`.
+    pub fn exclude<C: Component>(mut self) -> Self {
+        self.exclude.push(TypeId::of::<C>());
+        self
+    }
+
+    /// Only allow entities on these render layers to be hit.
+    pub fn with_render_layers(self, render_layers: RenderLayers) -> Self {
+        Self {
+            render_layers: Some(render_layers),
+            ..self
+        }
+    }
+
+    /// Only allow entities for which the registered `predicate` system returns `true`.
+    pub fn with_predicate(self, predicate: SystemId<In<Entity>, bool>) -> Self {
+        Self {
+            predicate: Some(predicate),
+            ..self
+        }
+    }
+
+    /// Returns `true` if the entity passes the component and render layer filters.
+    fn allows_components(&self, entity: EntityRef, world: &World) -> bool {
+        let has = |type_id: &TypeId| {
+            world
+                .components()
+                .get_id(*type_id)
+                .is_some_and(|id| entity.contains_id(id))
+        };
+        let included = self.include.is_empty() || self.include.iter().any(has);
+        let excluded = self.exclude.iter().any(has);
+        let on_layers = self.render_layers.as_ref().is_none_or(|layers| {
+            entity
+                .get::<RenderLayers>()
+                .unwrap_or(&RenderLayers::default())
+                .intersects(layers)
+        });
+        included && !excluded && on_layers
+    }
+}
+
+/// Optional. Controls where the anchor is placed when a motion starts on this camera, for each
+/// kind of motion. Without this component, the pointer hit is used for all motions.
+#[derive(Debug, Clone, Component)]
+pub struct AnchorSettings {
+    /// Where the anchor is placed when orbiting.
+    pub orbit: AnchorSource,
+    /// Where the anchor is placed when panning.
+    pub pan: AnchorSource,
+    /// Where the anchor is placed when only zooming.
+    pub zoom: AnchorSource,
+    /// Used to cast rays into the scene for [`AnchorSource::ScreenCenter`]. The rays are in world
+    /// space.
+    pub ray_cast: Option<RayCaster>,
+    /// Used in order to place the anchor when there is nothing under the pointer. If empty, or
+    /// none of the fallbacks find a point in front of the camera, the last anchor depth is used.
+    pub fallbacks: Vec<AnchorFallback>,
+    /// Decides which pointer hits can be used as an anchor.
+    pub filter: AnchorFilter,
+    /// The nearest filtered pointer hit when the current motion started, in world space.
+    pointer_hit: Option<DVec3>,
+    /// The result of the last screen center ray cast, in world space.
+    center_hit: Option<DVec3>,
+}
+
+impl Default for AnchorSettings {
+    fn default() -> Self {
+        Self::new(AnchorSource::PointerHit)
+    }
+}
+
+impl AnchorSettings {
+    /// Use the same anchor source for all kinds of motion.
+    pub fn new(source: AnchorSource) -> Self {
+        Self {
+            orbit: source,
+            pan: source,
+            zoom: source,
+            ray_cast: None,
+            fallbacks: Vec::new(),
+            filter: AnchorFilter::default(),
+            pointer_hit: None,
+            center_hit: None,
+        }
+    }
+
+    /// Set the ray caster, or ray casting system, used for [`AnchorSource::ScreenCenter`].
+    pub fn with_ray_cast(self, ray_cast: impl Into<RayCaster>) -> Self {
+        Self {
+            ray_cast: Some(ray_cast.into()),
+            ..self
+        }
+    }
+
+    /// Set the fallbacks used when there is nothing under the pointer.
+    pub fn with_fallbacks(self, fallbacks: impl IntoIterator<Item = AnchorFallback>) -> Self {
+        Self {
+            fallbacks: fallbacks.into_iter().collect(),
+            ..self
+        }
+    }
+
+    /// Set the filter used to decide which pointer hits can be used as an anchor.
+    pub fn with_filter(self, filter: AnchorFilter) -> Self {
+        Self { filter, ..self }
+    }
+
+    /// The anchor source used for this kind of motion.
+    pub fn source(&self, kind: MotionKind) -> AnchorSource {
+        match kind {
+            MotionKind::OrbitZoom => self.orbit,
+            MotionKind::PanZoom => self.pan,
+            MotionKind::Zoom => self.zoom,
+        }
+    }
+
+    /// The world space hit at the center of the viewport, found when the current motion started.
+    pub fn center_hit(&self) -> Option<DVec3> {
+        self.center_hit
+    }
+
+    /// The nearest pointer hit that passed the [`AnchorFilter`], found when the current motion
+    /// started, in world space.
+    pub fn pointer_hit(&self) -> Option<DVec3> {
+        self.pointer_hit
+    }
+
+    /// Find the nearest pointer hit that passes the [`AnchorFilter`] for cameras that are starting
+    /// a motion.
+    pub fn filter_hits(world: &mut World, mut cursor: Local<MessageCursor<EditorCamInputMessage>>) {
+        let requests = cursor
+            .read(world.resource::<Messages<EditorCamInputMessage>>())
+            .filter_map(|event| match event {
+                EditorCamInputMessage::Start {
+                    camera, pointer, ..
+                } => Some((*camera, *pointer)),
+                EditorCamInputMessage::End { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (camera, pointer) in requests {
+            let Some(settings) = world.get::<AnchorSettings>(camera) else {
+                continue;
+            };
+            let filter = settings.filter.clone();
+            let hits = world
+                .resource::<PointerMap>()
+                .get_entity(pointer)
+                .and_then(|entity| world.get::<PointerInteraction>(entity))
+                .map(|interaction| {
+                    interaction
+                        .iter()
+                        .filter_map(|(entity, hit)| Some((*entity, hit.position?)))
+                        .filter(|(entity, _)| {
+                            world.get_entity(*entity).is_ok_and(|entity| {
+                                // If the hit is on a window, we cannot use the `hit.position` as
+                                // an anchor as the `hit.position` is in viewport coordinates.
+                                !entity.contains::<Window>()
+                                    && filter.allows_components(entity, world)
+                            })
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+
+            // Hits are sorted from nearest to farthest, so the first hit that passes is used.
+            let hit = hits.into_iter().find_map(|(entity, position)| {
+                let Some(predicate) = filter.predicate else {
+                    return Some(position);
+                };
+                match world.run_system_with(predicate, entity) {
+                    Ok(allowed) => allowed.then_some(position),
+                    Err(err) => {
+                        error_once!("Unable to run the anchor filter predicate system: {err}");
+                        None
+                    }
+                }
+            });
+            if let Some(mut settings) = world.get_mut::<AnchorSettings>(camera) {
+                settings.pointer_hit = hit.map(|position| position.as_dvec3());
+            }
+        }
+    }
+
+    /// Cast rays through the center of the viewport for cameras that are starting a motion
+    /// anchored at [`AnchorSource::ScreenCenter`].
+    pub fn cast_center_rays(
+        world: &mut World,
+        mut cursor: Local<MessageCursor<EditorCamInputMessage>>,
+    ) {
+        let requests = cursor
+            .read(world.resource::<Messages<EditorCamInputMessage>>())
+            .filter_map(|event| match event {
+                EditorCamInputMessage::Start { kind, camera, .. } => Some((*kind, *camera)),
+                EditorCamInputMessage::End { .. } => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (kind, camera) in requests {
+            let Some(settings) = world.get::<AnchorSettings>(camera) else {
+                continue;
+            };
+            if settings.source(kind) != AnchorSource::ScreenCenter {
+                continue;
+            }
+            let ray_cast = settings.ray_cast;
+            let Some(transform) = world.get::<GlobalTransform>(camera).copied() else {
+                continue;
+            };
+            let (_, rotation, origin) = transform.to_scale_rotation_translation();
+            let ray = CameraRay {
+                camera,
+                origin: origin.as_dvec3(),
+                direction: (rotation.as_dquat() * DVec3::NEG_Z).normalize(),
+                max_distance: f64::INFINITY,
+            };
+            let hit = ray_cast
+                .and_then(|ray_cast| ray_cast.cast(world, ray))
+                .map(|distance| ray.origin + ray.direction * distance);
+            if let Some(mut settings) = world.get_mut::<AnchorSettings>(camera) {
+                settings.center_hit = hit;
+            }
+        }
+    }
+}
+
+/// The world space anchor for a motion started on a camera, if the [`AnchorSource`] defines one.
+/// Returns `None` if the pointer hit should be used.
+pub(crate) fn source_anchor(
+    settings: Option<&AnchorSettings>,
+    kind: MotionKind,
+    last_anchor_depth: f64,
+    cam_transform: &GlobalTransform,
+    selection: impl Iterator<Item = DVec3>,
+) -> Option<DVec3> {
+    let settings = settings?;
+    match settings.source(kind) {
+        AnchorSource::PointerHit => None,
+        AnchorSource::ScreenCenter => settings.center_hit.or_else(|| {
+            // Without a hit, the anchor is straight ahead at the last depth, in both projections.
+            Some(
+                EditorCam::view_to_world(cam_transform).transform_point3(DVec3::new(
+                    0.0,
+                    0.0,
+                    last_anchor_depth,
+                )),
+            )
+        }),
+        AnchorSource::Pivot(pivot) => Some(pivot),
+        AnchorSource::Selection => {
+            let (sum, count) = selection.fold((DVec3::ZERO, 0usize), |(sum, count), p| {
+                (sum + p, count + 1)
+            });
+            (count > 0).then(|| sum / count as f64)
+        }
+    }
+}
+
+/// The view space anchor found by the first [`AnchorFallback`] that places the anchor in front of
+/// the camera, given a view space pointer ray.
+pub(crate) fn fallback_anchor(
+    settings: Option<&AnchorSettings>,
+    (view_origin, view_direction): (DVec3, DVec3),
+    cam_transform: &GlobalTransform,
+    bounds: &Query<(&GlobalTransform, &Aabb), With<AnchorBounds>>,
+) -> Option<DVec3> {
+    let settings = settings?;
+    let view_to_world = EditorCam::view_to_world(cam_transform);
+    let world_to_view = view_to_world.inverse();
+    let origin = view_to_world.transform_point3(view_origin);
+    let direction = view_to_world
+        .transform_vector3(view_direction)
+        .normalize_or_zero();
+    if direction == DVec3::ZERO || !origin.is_finite() {
+        return None;
+    }
+    settings.fallbacks.iter().find_map(|fallback| {
+        fallback
+            .anchor(origin, direction, bounds)
+            .map(|world_space| world_to_view.transform_point3(world_space))
+            .filter(|p| p.is_finite() && p.z < 0.0)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::Vec3A;
+
+    use super::*;
+
+    fn unit_cube() -> Aabb {
+        Aabb {
+            center: Vec3A::ZERO,
+            half_extents: Vec3A::ONE,
+        }
+    }
+
+    #[test]
+    fn axis_aligned_ray_hits() {
+        let origin = DVec3::new(0.5, 0.0, 5.0);
+        let hit = nearest_on_aabb(DMat4::IDENTITY, &unit_cube(), origin, DVec3::NEG_Z);
+        assert_eq!(hit, Some((0.0, 4.0, DVec3::new(0.5, 0.0, 1.0))));
+    }
+
+    #[test]
+    fn axis_aligned_ray_on_face_hits() {
+        let origin = DVec3::new(1.0, -1.0, 5.0);
+        let hit = nearest_on_aabb(DMat4::IDENTITY, &unit_cube(), origin, DVec3::NEG_Z);
+        assert_eq!(hit, Some((0.0, 4.0, DVec3::new(1.0, -1.0, 1.0))));
+    }
+
+    #[test]
+    fn axis_aligned_ray_misses() {
+        let origin = DVec3::new(3.0, 0.0, 5.0);
+        let hit = nearest_on_aabb(DMat4::IDENTITY, &unit_cube(), origin, DVec3::NEG_Z);
+        assert_eq!(hit, Some((2.0, 5.0, DVec3::new(1.0, 0.0, 0.0))));
+    }
+}
@@ -0,0 +1,229 @@
+//! Controls how mouse wheel inputs are converted into zoom inputs by the [`DefaultInputPlugin`].
+//! See [`ScrollZoom`].
+//!
+//! [`DefaultInputPlugin`]: super::DefaultInputPlugin
+
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use bevy_math::prelude::*;
+use bevy_reflect::prelude::*;
+
+/// Zoom by a fixed step for each notch of the mouse wheel, easing to the new zoom level.
+#[derive(Debug, Clone, Reflect)]
+pub struct ZoomSteps {
+    /// The fraction of the distance to the anchor the camera moves for each notch when zooming
+    /// in. For orthographic projections, this is the fraction the projection scale shrinks by.
+    /// Zooming out by a notch exactly undoes zooming in by a notch.
+    pub step: f64,
+    /// How long it takes to ease to the new zoom level after each notch.
+    pub duration: Duration,
+    /// The cubic curve used to ease each step.
+    #[reflect(ignore)]
+    pub curve: CubicSegment<Vec2>,
+}
+
+impl Default for ZoomSteps {
+    fn default() -> Self {
+        Self {
+            step: 0.2,
+            duration: Duration::from_millis(150),
+            curve: CubicSegment::new_bezier_easing((0.0, 0.0), (0.25, 1.0)),
+        }
+    }
+}
+
+/// A wheel notch that has not finished easing.
+#[derive(Debug, Clone, Default)]
+struct PendingStep {
+    /// The number of notches, negative when zooming out.
+    notches: f64,
+    elapsed: Duration,
+    /// The eased progress that has already been sent to the controller.
+    progress: f64,
+}
+
+/// Optional. Controls how mouse wheel inputs are converted into zoom inputs for this camera.
+/// Without this component, scrolling zooms continuously, using
+/// [`ScrollZoom::DEFAULT_PIXELS_PER_LINE`].
+#[derive(Debug, Clone, Component, Reflect)]
+pub struct ScrollZoom {
+    /// Mouse wheels report scrolling in lines (notches) or pixels. This is the number of pixels of
+    /// zoom input for a line of scrolling, and can be tuned for mice with unusual wheels.
+    pub pixels_per_line: f32,
+    /// If set, each line of scrolling zooms by a fixed step, instead of zooming continuously.
+    pub steps: Option<ZoomSteps>,
+    #[reflect(ignore)]
+    pending: Vec<PendingStep>,
+}
+
+impl Default for ScrollZoom {
+    fn default() -> Self {
+        Self {
+            pixels_per_line: Self::DEFAULT_PIXELS_PER_LINE,
+            steps: None,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl ScrollZoom {
+    /// The default number of pixels of zoom input for a line of scrolling.
+    pub const DEFAULT_PIXELS_PER_LINE: f32 = 150.0;
+
+    /// Zoom by a fixed fraction of the distance to the anchor for each wheel notch.
+    pub fn stepped(step: f64) -> Self {
+        Self {
+            steps: Some(ZoomSteps {
+                step,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Set the number of pixels of zoom input for a line of scrolling.
+    pub fn with_pixels_per_line(self, pixels_per_line: f32) -> Self {
+        Self {
+            pixels_per_line,
+            ..self
+        }
+    }
+
+    /// Returns `true` if a zoom step is still easing to its new zoom level.
+    pub fn is_stepping(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Convert this frame's scrolling, in lines, into a zoom input for the controller. Returns the
+    /// zoom input, and `true` if it is an eased step that should be sent with
+    /// [`EditorCam::send_unsmoothed_zoom_input`].
+    ///
+    /// `fraction_per_input` is the relative zoom caused by a unit of zoom input, see
+    /// [`EditorCam::zoom_fraction_per_input`]. If it is `None`, the zoom is continuous.
+    ///
+    /// [`EditorCam::zoom_fraction_per_input`]: crate::prelude::EditorCam::zoom_fraction_per_input
+    /// [`EditorCam::send_unsmoothed_zoom_input`]: crate::prelude::EditorCam::send_unsmoothed_zoom_input
+    pub(crate) fn zoom_input(
+        &mut self,
+        lines: f32,
+        delta_time: Duration,
+        fraction_per_input: Option<f64>,
+    ) -> (f32, bool) {
+        let (Some(steps), Some(fraction_per_input)) = (&self.steps, fraction_per_input) else {
+            self.pending.clear();
+            return (lines * self.pixels_per_line, false);
+        };
+        if lines != 0.0 {
+            self.pending.push(PendingStep {
+                notches: lines as f64,
+                ..Default::default()
+            });
+        }
+
+        // Steps are applied in log space, so simultaneous steps compose, and zooming in and out by
+        // the same number of notches returns to the same zoom level.
+        let log_scale_per_notch = (1.0 - steps.step.clamp(0.0, 0.99)).ln();
+        let mut log_scale = 0.0;
+        for pending in &mut self.pending {
+            pending.elapsed += delta_time;
+            let progress = if pending.elapsed >= steps.duration {
+                1.0
+            } else {
+                let t = pending.elapsed.as_secs_f32() / steps.duration.as_secs_f32();
+                steps.curve.ease(t) as f64
+            };
+            log_scale += pending.notches * log_scale_per_notch * (progress - pending.progress);
+            pending.progress = progress;
+        }
+        self.pending
+            .retain(|pending| pending.elapsed < steps.duration);
+
+        // The fraction of the distance to the anchor to move this frame.
+        let fraction = 1.0 - log_scale.exp();
+        ((fraction / fraction_per_input) as f32, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_camera::{prelude::*, RenderTargetInfo};
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::{DVec3, UVec2};
+    use bevy_time::prelude::*;
+    use bevy_transform::prelude::*;
+    use bevy_window::RequestRedraw;
+
+    use super::*;
+    use crate::{controller::zoom::ZoomLimitReached, prelude::*};
+
+    const FRAME: Duration = Duration::from_micros(16_667);
+
+    fn camera_world(anchor_depth: f64) -> (World, Entity) {
+        let mut world = World::new();
+        world.init_resource::<Time>();
+        world.init_resource::<TransformAdapter>();
+        world.init_resource::<ProjectionAdapters>();
+        world.init_resource::<Messages<RequestRedraw>>();
+        world.init_resource::<Messages<ZoomLimitReached>>();
+
+        let mut projection = Projection::Perspective(PerspectiveProjection::default());
+        projection.update(1280.0, 720.0);
+        let mut camera = Camera::default();
+        camera.computed.target_info = Some(RenderTargetInfo {
+            physical_size: UVec2::new(1280, 720),
+            scale_factor: 1.0,
+        });
+        camera.computed.clip_from_view = projection.get_clip_from_view();
+        let editor_cam = EditorCam::default().with_initial_anchor_depth(anchor_depth);
+        let entity = world
+            .spawn((editor_cam, camera, projection, Transform::default()))
+            .id();
+        (world, entity)
+    }
+
+    /// Scroll `lines` on the first frame, then run frames until the step has finished, and
+    /// momentum would have had time to move the camera.
+    fn scroll(world: &mut World, entity: Entity, scroll_zoom: &mut ScrollZoom, lines: f32) {
+        world.get_mut::<EditorCam>(entity).unwrap().start_zoom(None);
+        for frame in 0..60 {
+            let mut entity_mut = world.entity_mut(entity);
+            if frame > 0 && !scroll_zoom.is_stepping() {
+                // Momentum from the steps would keep moving the camera after this.
+                entity_mut.get_mut::<EditorCam>().unwrap().end_move();
+            } else {
+                let fraction_per_input = {
+                    let camera = entity_mut.get::<Camera>().unwrap().clone();
+                    let projection = entity_mut.get::<Projection>().unwrap().clone();
+                    let editor_cam = entity_mut.get::<EditorCam>().unwrap();
+                    editor_cam.zoom_fraction_per_input(&camera, &projection)
+                };
+                let lines = if frame == 0 { lines } else { 0.0 };
+                let (zoom, is_stepped) = scroll_zoom.zoom_input(lines, FRAME, fraction_per_input);
+                assert!(is_stepped);
+                let mut editor_cam = entity_mut.get_mut::<EditorCam>().unwrap();
+                editor_cam.send_unsmoothed_zoom_input(zoom);
+            }
+            world.resource_mut::<Time>().advance_by(FRAME);
+            world
+                .run_system_once(EditorCam::update_camera_positions)
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn stepped_zoom_moves_exactly_one_step() {
+        let (mut world, entity) = camera_world(10.0);
+        let mut scroll_zoom = ScrollZoom::stepped(0.2);
+
+        scroll(&mut world, entity, &mut scroll_zoom, 1.0);
+        let translation = world.get::<Transform>(entity).unwrap().translation;
+        let distance = (DVec3::new(0.0, 0.0, -10.0) - translation.as_dvec3()).length();
+        assert!((distance - 8.0).abs() < 1e-4, "anchor distance {distance}");
+
+        scroll(&mut world, entity, &mut scroll_zoom, -1.0);
+        let translation = world.get::<Transform>(entity).unwrap().translation;
+        let distance = (DVec3::new(0.0, 0.0, -10.0) - translation.as_dvec3()).length();
+        assert!((distance - 10.0).abs() < 1e-4, "anchor distance {distance}");
+    }
+}
@@ -148,6 +148,10 @@
 //! as the camera isn't being controlled by the user while you do this. This means you can control
 //! this camera with another camera controller, or use it in a floating origin system.
 //!
+//! The `big_space` feature adds [`TransformAdapter::big_space`](prelude::TransformAdapter), which
+//! reads the camera position from its `big_space` grid cell, so positions used by the controller
+//! and extensions are unaffected when the floating origin is recentered.
+//!
 //! ### Independent skybox
 //!
 //! When working in a CAD context, it is common to use orthographic projections to remove
@@ -175,7 +179,7 @@ pub mod input;
 /// Common imports.
 pub mod prelude {
     pub use crate::{
-        controller::{component::*, transform_adapter::*, *},
+        controller::{component::*, projection_adapter::*, transform_adapter::*, *},
         DefaultEditorCamPlugins,
     };
 }
@@ -192,9 +196,18 @@ use bevy_ecs::prelude::SystemSet;
 ///
 /// - [`controller::MinimalEditorCamPlugin`]
 /// - [`input::DefaultInputPlugin`]
+/// - [`extensions::tween::TweenPlugin`]
+/// - [`extensions::clip_planes::ClipPlanesPlugin`]
+/// - [`extensions::collision::CollisionPlugin`]
 /// - [`extensions::dolly_zoom::DollyZoomPlugin`]
+/// - [`extensions::follow::FollowPlugin`]
+/// - [`extensions::frame::FramePlugin`]
 /// - [`extensions::look_to::LookToPlugin`]
+/// - [`extensions::turntable::TurntablePlugin`]
 /// - [`extensions::anchor_indicator::AnchorIndicatorPlugin`] (if the `extension_anchor_indicator` feature is enabled)
+/// - `extensions::bookmarks::BookmarksPlugin` (if the `extension_bookmarks` feature is enabled)
+/// - `extensions::flythrough::FlythroughPlugin` (if the `extension_flythrough` feature is enabled)
+/// - `extensions::history::ViewHistoryPlugin` (if the `extension_history` feature is enabled)
 /// - [`extensions::independent_skybox::IndependentSkyboxPlugin`] (if the `extension_independent_skybox` feature is enabled)
 pub struct DefaultEditorCamPlugins;
 
@@ -202,18 +215,40 @@ pub struct DefaultEditorCamPlugins;
 #[derive(SystemSet, Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct SyncCameraPosition;
 
+/// This system set copies data from the parents of cameras onto the cameras, for the
+/// [`TransformAdapter`](crate::controller::transform_adapter::TransformAdapter). It runs before
+/// [`SyncCameraPosition`] in the `PreUpdate` schedule, and after transform propagation in the
+/// `PostUpdate` schedule.
+#[derive(SystemSet, Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SyncCameraParents;
+
 impl PluginGroup for DefaultEditorCamPlugins {
     #[allow(clippy::let_and_return)] // Needed for conditional compilation
     fn build(self) -> PluginGroupBuilder {
         let group = PluginGroupBuilder::start::<Self>()
             .add(input::DefaultInputPlugin)
             .add(controller::MinimalEditorCamPlugin)
+            .add(extensions::tween::TweenPlugin)
+            .add(extensions::clip_planes::ClipPlanesPlugin)
+            .add(extensions::collision::CollisionPlugin)
             .add(extensions::dolly_zoom::DollyZoomPlugin)
-            .add(extensions::look_to::LookToPlugin);
+            .add(extensions::follow::FollowPlugin)
+            .add(extensions::frame::FramePlugin)
+            .add(extensions::look_to::LookToPlugin)
+            .add(extensions::turntable::TurntablePlugin);
 
         #[cfg(feature = "extension_anchor_indicator")]
         let group = group.add(extensions::anchor_indicator::AnchorIndicatorPlugin);
 
+        #[cfg(feature = "extension_bookmarks")]
+        let group = group.add(extensions::bookmarks::BookmarksPlugin);
+
+        #[cfg(feature = "extension_flythrough")]
+        let group = group.add(extensions::flythrough::FlythroughPlugin);
+
+        #[cfg(feature = "extension_history")]
+        let group = group.add(extensions::history::ViewHistoryPlugin);
+
         #[cfg(feature = "extension_independent_skybox")]
         let group = group.add(extensions::independent_skybox::IndependentSkyboxPlugin);
 